@@ -0,0 +1,213 @@
+//!
+//! Synchronous wrapper around `WarframeClient` for code that doesn't run inside an async
+//! runtime, enabled with the `blocking` feature
+//!
+use crate::error::{Result, WarframeError};
+use crate::localized::Localize;
+use crate::model::*;
+use crate::{drops, items, profile, rivens};
+use crate::{ReqwestTransport, Snapshot, Transport};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::runtime::{Builder, Runtime};
+
+///
+/// Generates a blocking getter for every worldstate section of `model::Platform`
+///
+macro_rules! blocking_getters {
+    ($($(#[$meta:meta])* $name:ident => $path:literal: $ty:ty;)*) => {
+        $(
+            $(#[$meta])*
+            pub fn $name(
+                &self,
+                platform: impl Into<Option<PlatformType>>,
+                lang: impl Into<Option<Language>>,
+            ) -> Result<$ty> {
+                self.runtime.block_on(self.inner.$name(platform, lang))
+            }
+        )*
+    };
+}
+
+///
+/// Generates a blocking getter for every localized section, see `for_each_localized_section`
+///
+macro_rules! blocking_localized_getters {
+    ($($(#[$meta:meta])* $name:ident => $path:literal: $ty:ty;)*) => {
+        $(
+            $(#[$meta])*
+            pub fn $name(
+                &self,
+                platform: impl Into<Option<PlatformType>>,
+                langs: &[Language],
+            ) -> Result<<$ty as Localize>::Localized> {
+                self.runtime.block_on(self.inner.$name(platform, langs))
+            }
+        )*
+    };
+}
+
+///
+/// Generates blocking getters and searches for static data, see `crate::items`
+///
+macro_rules! blocking_item_getters {
+    ($($get:ident, $search:ident: $ty:ty;)*) => {
+        $(
+            #[doc = concat!("See `crate::WarframeClient::", stringify!($get), "`")]
+            pub fn $get(&self, lang: impl Into<Option<Language>>) -> Result<Vec<$ty>> {
+                self.runtime.block_on(self.inner.$get(lang))
+            }
+
+            #[doc = concat!("See `crate::WarframeClient::", stringify!($search), "`")]
+            pub fn $search(
+                &self,
+                name: &str,
+                lang: impl Into<Option<Language>>,
+            ) -> Result<Vec<$ty>> {
+                self.runtime.block_on(self.inner.$search(name, lang))
+            }
+        )*
+    };
+}
+
+///
+/// Blocking WarframeClient, every getter of `crate::WarframeClient` blocks the current thread
+/// until it's done
+///
+/// Runs its own single threaded runtime, so it must not be used from within an async context
+///
+#[derive(Debug, Clone)]
+pub struct WarframeClient<T: Transport = ReqwestTransport> {
+    inner: crate::WarframeClient<T>,
+    runtime: Arc<Runtime>,
+}
+
+impl WarframeClient {
+    ///
+    /// Create new blocking WarframeClient with the defaults of `crate::WarframeClient`
+    ///
+    pub fn new() -> Result<Self> {
+        Self::with_client(crate::WarframeClient::new())
+    }
+
+    ///
+    /// Create a blocking WarframeClient that serves every getter from the saved worldstates in
+    /// `dir`, see `Snapshot`
+    ///
+    pub fn from_snapshot(dir: impl Into<std::path::PathBuf>) -> Result<Self> {
+        Self::with_client(
+            crate::WarframeClient::builder()
+                .snapshot(Snapshot::new(dir))
+                .build()?,
+        )
+    }
+}
+
+impl<T: Transport> WarframeClient<T> {
+    ///
+    /// Wrap an async client, configured with `crate::WarframeClientBuilder`
+    ///
+    pub fn with_client(inner: crate::WarframeClient<T>) -> Result<Self> {
+        let runtime = Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(|err| WarframeError::Io(Arc::new(err)))?;
+        Ok(Self {
+            inner,
+            runtime: Arc::new(runtime),
+        })
+    }
+
+    ///
+    /// The wrapped async client
+    ///
+    pub fn inner(&self) -> &crate::WarframeClient<T> {
+        &self.inner
+    }
+
+    ///
+    /// Get the whole data for a `model::Platform` and a choosen `Language`
+    ///
+    /// `platform` and `lang` fall back to the defaults of the client when `None`
+    ///
+    pub fn get_platform(
+        &self,
+        platform: impl Into<Option<PlatformType>>,
+        lang: impl Into<Option<Language>>,
+    ) -> Result<Platform> {
+        self.runtime
+            .block_on(self.inner.get_platform(platform, lang))
+    }
+
+    ///
+    /// Get the worldstates of every platform, see `crate::WarframeClient::get_all_platforms`
+    ///
+    pub fn get_all_platforms(
+        &self,
+        lang: impl Into<Option<Language>>,
+    ) -> HashMap<PlatformType, Result<Platform>> {
+        self.runtime.block_on(self.inner.get_all_platforms(lang))
+    }
+
+    ///
+    /// Get the public profile of a player, see `crate::WarframeClient::get_profile`
+    ///
+    pub fn get_profile(
+        &self,
+        username: &str,
+        platform: impl Into<Option<PlatformType>>,
+    ) -> Result<profile::Profile> {
+        self.runtime
+            .block_on(self.inner.get_profile(username, platform))
+    }
+
+    ///
+    /// Get the riven trade statistics, see `crate::WarframeClient::get_rivens`
+    ///
+    pub fn get_rivens(
+        &self,
+        platform: impl Into<Option<PlatformType>>,
+    ) -> Result<rivens::RivenStats> {
+        self.runtime.block_on(self.inner.get_rivens(platform))
+    }
+
+    ///
+    /// Get every drop as an index for offline lookups, see
+    /// `crate::WarframeClient::get_drop_index`
+    ///
+    pub fn get_drop_index(&self, lang: impl Into<Option<Language>>) -> Result<drops::DropIndex> {
+        self.runtime.block_on(self.inner.get_drop_index(lang))
+    }
+
+    for_each_section!(blocking_getters);
+
+    for_each_localized_section!(blocking_localized_getters);
+
+    blocking_item_getters! {
+        get_items, search_items: items::Item;
+        get_warframes, search_warframes: items::Warframe;
+        get_weapons, search_weapons: items::Weapon;
+        get_mods, search_mods: items::Mod;
+        get_arcanes, search_arcanes: items::Arcane;
+        get_relics, search_relics: items::Relic;
+        get_drops, search_drops: drops::DropEntry;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::WarframeClient;
+    use crate::transport::FixtureTransport;
+
+    #[test]
+    pub fn test_blocking_getters() -> Result<(), Box<dyn std::error::Error>> {
+        let fixtures = FixtureTransport::new().with_json("/pc/kuva", "[]");
+        let client = WarframeClient::with_client(
+            crate::WarframeClient::builder().build_with_transport(fixtures.clone())?,
+        )?;
+        assert!(client.get_kuva(None, None)?.is_empty());
+        assert!(client.get_kuva(None, None)?.is_empty());
+        assert_eq!(fixtures.requests().len(), 1);
+        Ok(())
+    }
+}
@@ -0,0 +1,213 @@
+use crate::error::Result;
+use crate::model::{Language, PlatformType};
+use crate::{
+    CacheBackend, CachePolicy, ReqwestTransport, RetryPolicy, Snapshot, Transport, WarframeCache,
+    WarframeClient, API_URL,
+};
+use reqwest::Client as HttpClient;
+use std::sync::Arc;
+use std::time::Duration;
+use url::Url;
+
+const USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
+
+///
+/// Builder to configure a `WarframeClient`
+///
+/// ```no_run
+/// # use warframestat_rs::WarframeClient;
+/// # use warframestat_rs::model::{Language, PlatformType};
+/// # use std::time::Duration;
+/// let client = WarframeClient::builder()
+///     .base_url("http://localhost:3001")
+///     .timeout(Duration::from_secs(10))
+///     .platform(PlatformType::PS4)
+///     .language(Language::German)
+///     .build()
+///     .unwrap();
+/// ```
+///
+#[derive(Debug, Clone)]
+pub struct WarframeClientBuilder {
+    base_url: String,
+    http: Option<HttpClient>,
+    connect_timeout: Option<Duration>,
+    timeout: Option<Duration>,
+    user_agent: String,
+    platform: PlatformType,
+    language: Language,
+    cache_policy: CachePolicy,
+    cache_backend: Option<Arc<dyn CacheBackend>>,
+    retry_policy: RetryPolicy,
+    snapshot: Option<Snapshot>,
+    concurrency: usize,
+}
+
+impl Default for WarframeClientBuilder {
+    fn default() -> Self {
+        Self {
+            base_url: API_URL.to_string(),
+            http: None,
+            connect_timeout: None,
+            timeout: None,
+            user_agent: USER_AGENT.to_string(),
+            platform: PlatformType::default(),
+            language: Language::default(),
+            cache_policy: CachePolicy::default(),
+            cache_backend: None,
+            retry_policy: RetryPolicy::default(),
+            snapshot: None,
+            concurrency: 4,
+        }
+    }
+}
+
+impl WarframeClientBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    ///
+    /// Url of the Warframestat instance, e.g. a self-hosted one or a local mock
+    ///
+    pub fn base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into();
+        self
+    }
+
+    ///
+    /// Use an already configured `reqwest::Client`
+    ///
+    /// Timeouts and the user agent of this builder are ignored in that case, configure them
+    /// on the passed client instead
+    ///
+    pub fn http_client(mut self, http: HttpClient) -> Self {
+        self.http = Some(http);
+        self
+    }
+
+    ///
+    /// Timeout for establishing the connection
+    ///
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    ///
+    /// Timeout for the whole request, from connecting until the body has been read
+    ///
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    ///
+    /// `User-Agent` header sent with every request
+    ///
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = user_agent.into();
+        self
+    }
+
+    ///
+    /// Platform used when a getter isn't given one
+    ///
+    pub fn platform(mut self, platform: PlatformType) -> Self {
+        self.platform = platform;
+        self
+    }
+
+    ///
+    /// Language used when a getter isn't given one
+    ///
+    pub fn language(mut self, language: Language) -> Self {
+        self.language = language;
+        self
+    }
+
+    ///
+    /// How long responses are cached, see `CachePolicy`
+    ///
+    pub fn cache_policy(mut self, cache_policy: CachePolicy) -> Self {
+        self.cache_policy = cache_policy;
+        self
+    }
+
+    ///
+    /// Where responses are cached, in memory by default, see `CacheBackend`
+    ///
+    pub fn cache_backend(mut self, backend: impl CacheBackend + 'static) -> Self {
+        self.cache_backend = Some(Arc::new(backend));
+        self
+    }
+
+    ///
+    /// How failed requests are retried, see `RetryPolicy`
+    ///
+    pub fn retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    ///
+    /// Serve the worldstate getters from saved worldstates instead of the api, nothing is
+    /// requested over the network then
+    ///
+    /// Getters of data that isn't part of a worldstate, like the item database, drops,
+    /// profiles and riven stats, fail with `WarframeError::NotInSnapshot`
+    ///
+    pub fn snapshot(mut self, snapshot: Snapshot) -> Self {
+        self.snapshot = Some(snapshot);
+        self
+    }
+
+    ///
+    /// How many requests a single call like `WarframeClient::get_all_platforms` sends at once,
+    /// defaults to 4
+    ///
+    pub fn concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency;
+        self
+    }
+
+    pub fn build(self) -> Result<WarframeClient> {
+        let http = match &self.http {
+            Some(http) => http.clone(),
+            None => {
+                let mut builder = HttpClient::builder().user_agent(&self.user_agent);
+                if let Some(timeout) = self.connect_timeout {
+                    builder = builder.connect_timeout(timeout);
+                }
+                if let Some(timeout) = self.timeout {
+                    builder = builder.timeout(timeout);
+                }
+                builder.build()?
+            }
+        };
+        self.build_with_transport(ReqwestTransport::new(http))
+    }
+
+    ///
+    /// Build a client that sends its requests with `transport` instead of reqwest
+    ///
+    /// The http client, timeouts and user agent of this builder are ignored
+    ///
+    pub fn build_with_transport<T: Transport>(self, transport: T) -> Result<WarframeClient<T>> {
+        let base_url = Url::parse(&self.base_url)?;
+        Ok(WarframeClient {
+            base_url,
+            transport,
+            cache: match self.cache_backend {
+                Some(backend) => WarframeCache::with_backend(backend),
+                None => WarframeCache::new(),
+            },
+            platform: self.platform,
+            language: self.language,
+            cache_policy: self.cache_policy,
+            retry_policy: self.retry_policy,
+            snapshot: self.snapshot,
+            concurrency: self.concurrency,
+        })
+    }
+}
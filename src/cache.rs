@@ -0,0 +1,446 @@
+use crate::error::{self, WarframeError};
+use crate::model::{Language, PlatformType};
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Local};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fmt::{self, Debug, Display};
+use std::future::Future;
+use std::hash::Hash;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use tokio::sync::{Mutex, OnceCell};
+
+///
+/// Defines whether and for how long the responses of a `WarframeClient` are cached
+///
+/// A response is cached until the earliest expiry found in it (see `model::EarliestExpiry`),
+/// clamped between `min_ttl` and `max_ttl`. Responses without any expiry are cached for `ttl`.
+///
+/// Static data like the item database only changes with game updates and is cached for
+/// `static_ttl` instead.
+///
+/// Defaults to a `ttl` of 60s, clamped between 10s and 5min, and a `static_ttl` of a day
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CachePolicy {
+    pub enabled: bool,
+    pub ttl: std::time::Duration,
+    pub min_ttl: std::time::Duration,
+    pub max_ttl: std::time::Duration,
+    pub static_ttl: std::time::Duration,
+}
+
+impl Default for CachePolicy {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            ttl: std::time::Duration::from_secs(60),
+            min_ttl: std::time::Duration::from_secs(10),
+            max_ttl: std::time::Duration::from_secs(5 * 60),
+            static_ttl: std::time::Duration::from_secs(24 * 60 * 60),
+        }
+    }
+}
+
+impl CachePolicy {
+    ///
+    /// Never cache, every call results in a request
+    ///
+    pub fn disabled() -> Self {
+        Self {
+            enabled: false,
+            ..Self::default()
+        }
+    }
+
+    ///
+    /// When a response with the given earliest expiry should be evicted from the cache
+    ///
+    /// An expiry that already passed is treated like no expiry, the response is cached for
+    /// `ttl`
+    ///
+    pub fn expiration_time(
+        &self,
+        earliest_expiry: Option<DateTime<Local>>,
+    ) -> Option<DateTime<Local>> {
+        let now = Local::now();
+        let ttl = match earliest_expiry {
+            Some(expiry) if expiry > now => (expiry - now)
+                .to_std()
+                .unwrap_or_default()
+                .max(self.min_ttl)
+                .min(self.max_ttl),
+            _ => self.ttl,
+        };
+        now.checked_add_signed(Duration::from_std(ttl).ok()?)
+    }
+
+    ///
+    /// When static data fetched now should be evicted from the cache
+    ///
+    pub fn static_expiration_time(&self) -> Option<DateTime<Local>> {
+        Local::now().checked_add_signed(Duration::from_std(self.static_ttl).ok()?)
+    }
+}
+
+///
+/// defines a cache entry to validate cache entries based on the `timestamp`
+///
+/// `etag` and `last_modified` are the validators the api sent along with the entry, they are
+/// used to revalidate the entry once it expired
+///
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheEntry<T>
+where
+    T: Clone,
+{
+    pub entry: T,
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    expiration_time: DateTime<Local>,
+}
+
+impl<T: Clone> CacheEntry<T> {
+    pub fn new(entry: T, expiration_time: DateTime<Local>) -> Self {
+        Self {
+            entry,
+            etag: None,
+            last_modified: None,
+            expiration_time,
+        }
+    }
+
+    pub fn with_validators(mut self, etag: Option<String>, last_modified: Option<String>) -> Self {
+        self.etag = etag;
+        self.last_modified = last_modified;
+        self
+    }
+
+    pub fn is_expired(&self) -> bool {
+        Local::now() >= self.expiration_time
+    }
+
+    pub(crate) fn has_validators(&self) -> bool {
+        self.etag.is_some() || self.last_modified.is_some()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct CacheKey<T>
+where
+    T: Hash + PartialEq + Eq,
+{
+    pub key_value: T,
+    pub language: Language,
+    pub platform: PlatformType,
+}
+
+impl<T: Hash + PartialEq + Eq> CacheKey<T> {
+    fn new(key_value: T, language: Language, platform: PlatformType) -> Self {
+        Self {
+            key_value,
+            language,
+            platform,
+        }
+    }
+}
+
+impl<T: Hash + PartialEq + Eq + Display> Display for CacheKey<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}/{}", self.platform, self.language, self.key_value)
+    }
+}
+
+///
+/// Storage of a `WarframeCache`
+///
+/// Keys are built from the endpoint, language and platform of an entry, e.g. `pc/en/alerts`.
+/// Implement this to share cached responses between processes, see `DiskBackend`.
+///
+#[async_trait]
+pub trait CacheBackend: Debug + Send + Sync {
+    ///
+    /// get an entry, even if it's expired
+    ///
+    async fn get(&self, key: &str) -> error::Result<Option<CacheEntry<String>>>;
+
+    async fn insert(&self, key: &str, entry: CacheEntry<String>) -> error::Result<()>;
+
+    ///
+    /// extend the expiration time of an entry, returns `false` if there's no such entry
+    ///
+    async fn refresh(&self, key: &str, expiration_time: DateTime<Local>) -> error::Result<bool> {
+        match self.get(key).await? {
+            Some(mut entry) => {
+                entry.expiration_time = expiration_time;
+                self.insert(key, entry).await?;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+}
+
+///
+/// Default backend, keeps every entry in memory
+///
+#[derive(Debug, Default)]
+pub struct MemoryBackend {
+    map: Mutex<HashMap<String, CacheEntry<String>>>,
+}
+
+impl MemoryBackend {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl CacheBackend for MemoryBackend {
+    async fn get(&self, key: &str) -> error::Result<Option<CacheEntry<String>>> {
+        Ok(self.map.lock().await.get(key).cloned())
+    }
+
+    async fn insert(&self, key: &str, entry: CacheEntry<String>) -> error::Result<()> {
+        self.map.lock().await.insert(key.to_string(), entry);
+        Ok(())
+    }
+
+    async fn refresh(&self, key: &str, expiration_time: DateTime<Local>) -> error::Result<bool> {
+        match self.map.lock().await.get_mut(key) {
+            Some(entry) => {
+                entry.expiration_time = expiration_time;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+}
+
+static TMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// leaves room for `.json` and the temp file suffix within the common 255 byte limit
+const MAX_FILE_STEM: usize = 200;
+
+///
+/// Backend that stores one json file per entry in a directory, so multiple processes and
+/// restarts share the same cache
+///
+/// Files are replaced atomically, unreadable files are treated as missing
+///
+#[derive(Debug, Clone)]
+pub struct DiskBackend {
+    dir: PathBuf,
+}
+
+impl DiskBackend {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    ///
+    /// file of `key`, every byte except lowercase letters and digits is percent encoded so
+    /// different keys never share a file, even on case insensitive file systems
+    ///
+    /// Names that would get too long for the file system, e.g. for long search queries, are
+    /// replaced with the sha256 of the key. Encoded names never contain a bare `-`, so they
+    /// can't collide with the hashed ones
+    ///
+    fn path(&self, key: &str) -> PathBuf {
+        let mut file_name = String::with_capacity(key.len() + 5);
+        for byte in key.bytes() {
+            if byte.is_ascii_lowercase() || byte.is_ascii_digit() {
+                file_name.push(byte as char);
+            } else {
+                file_name.push_str(&format!("%{byte:02X}"));
+            }
+        }
+        if file_name.len() > MAX_FILE_STEM {
+            let digest = Sha256::digest(key.as_bytes());
+            file_name = digest
+                .iter()
+                .fold(String::from("sha256-"), |mut name, byte| {
+                    name.push_str(&format!("{byte:02x}"));
+                    name
+                });
+        }
+        self.dir.join(file_name + ".json")
+    }
+}
+
+#[async_trait]
+impl CacheBackend for DiskBackend {
+    async fn get(&self, key: &str) -> error::Result<Option<CacheEntry<String>>> {
+        match tokio::fs::read(self.path(key)).await {
+            Ok(content) => Ok(serde_json::from_slice(&content).ok()),
+            Err(err) if err.kind() == ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(WarframeError::Cache(err.to_string())),
+        }
+    }
+
+    async fn insert(&self, key: &str, entry: CacheEntry<String>) -> error::Result<()> {
+        let path = self.path(key);
+        let content =
+            serde_json::to_vec(&entry).map_err(|err| WarframeError::Cache(err.to_string()))?;
+        // unique per write, concurrent writes of the same key must not share a temp file
+        let tmp = path.with_extension(format!(
+            "{}.{}.tmp",
+            std::process::id(),
+            TMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        let write = async {
+            tokio::fs::create_dir_all(&self.dir).await?;
+            tokio::fs::write(&tmp, content).await?;
+            tokio::fs::rename(&tmp, &path).await
+        };
+        write
+            .await
+            .map_err(|err| WarframeError::Cache(err.to_string()))
+    }
+}
+
+type Flight = Arc<OnceCell<error::Result<String>>>;
+
+///
+/// Cache to cache the results of `WarframeClient`. This threadsafe because it internally uses an
+/// arc mutex
+///
+/// Entries are stored in a `CacheBackend`, in memory by default. Concurrent misses for the same
+/// key can be coalesced into a single request with `WarframeCache::single_flight`
+///
+#[derive(Debug, Clone)]
+pub struct WarframeCache<T: Hash + PartialEq + Eq> {
+    backend: Arc<dyn CacheBackend>,
+    in_flight: Arc<Mutex<HashMap<CacheKey<T>, Flight>>>,
+}
+
+impl<T: Hash + PartialEq + Eq + Display> Default for WarframeCache<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Hash + PartialEq + Eq + Display> WarframeCache<T> {
+    pub fn new() -> Self {
+        Self::with_backend(Arc::new(MemoryBackend::new()))
+    }
+
+    pub fn with_backend(backend: Arc<dyn CacheBackend>) -> Self {
+        Self {
+            backend,
+            in_flight: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    ///
+    /// get a json from the cache, returns `None` if not cached
+    ///
+    pub async fn get(
+        &self,
+        key: T,
+        language: Language,
+        platform: PlatformType,
+    ) -> error::Result<Option<String>> {
+        let entry = self.get_entry(key, language, platform).await?;
+        Ok(entry
+            .filter(|entry| !entry.is_expired())
+            .map(|entry| entry.entry))
+    }
+
+    ///
+    /// get an entry from the cache even if it's expired, to revalidate it
+    ///
+    pub async fn get_entry(
+        &self,
+        key: T,
+        language: Language,
+        platform: PlatformType,
+    ) -> error::Result<Option<CacheEntry<String>>> {
+        let key = CacheKey::new(key, language, platform);
+        self.backend.get(&key.to_string()).await
+    }
+
+    pub async fn insert(
+        &self,
+        key: T,
+        language: Language,
+        platform: PlatformType,
+        entry: String,
+        expiration_time: DateTime<Local>,
+    ) -> error::Result<()> {
+        self.insert_entry(
+            key,
+            language,
+            platform,
+            CacheEntry::new(entry, expiration_time),
+        )
+        .await
+    }
+
+    pub async fn insert_entry(
+        &self,
+        key: T,
+        language: Language,
+        platform: PlatformType,
+        entry: CacheEntry<String>,
+    ) -> error::Result<()> {
+        let key = CacheKey::new(key, language, platform);
+        self.backend.insert(&key.to_string(), entry).await
+    }
+
+    ///
+    /// extend the expiration time of an entry after it was revalidated, returns `false` if
+    /// there's no such entry
+    ///
+    pub async fn refresh(
+        &self,
+        key: T,
+        language: Language,
+        platform: PlatformType,
+        expiration_time: DateTime<Local>,
+    ) -> error::Result<bool> {
+        let key = CacheKey::new(key, language, platform);
+        self.backend
+            .refresh(&key.to_string(), expiration_time)
+            .await
+    }
+}
+
+impl<T: Hash + PartialEq + Eq + Clone> WarframeCache<T> {
+    ///
+    /// Run `fetch` unless a fetch for the same key is already in flight, in which case its
+    /// result is awaited and shared instead, including its error
+    ///
+    /// If the caller running `fetch` is cancelled, one of the waiting callers takes over
+    ///
+    pub async fn single_flight<F, Fut>(
+        &self,
+        key: T,
+        language: Language,
+        platform: PlatformType,
+        fetch: F,
+    ) -> error::Result<String>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = error::Result<String>>,
+    {
+        let key = CacheKey::new(key, language, platform);
+        let flight = {
+            let mut in_flight = self.in_flight.lock().await;
+            in_flight.entry(key.clone()).or_default().clone()
+        };
+        let res = flight.get_or_init(fetch).await.clone();
+        let mut in_flight = self.in_flight.lock().await;
+        if in_flight
+            .get(&key)
+            .is_some_and(|current| Arc::ptr_eq(current, &flight))
+        {
+            in_flight.remove(&key);
+        }
+        res
+    }
+}
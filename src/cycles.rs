@@ -0,0 +1,348 @@
+//!
+//! Offline calculation of the open world cycles
+//!
+//! The cycles loop with fixed periods from known epochs, so they can be calculated for any
+//! instant without asking the api. Cetus, the Cambion Drift and the Zariman follow the Cetus
+//! bounty rotation, which may drift by a few seconds.
+//!
+use crate::model::*;
+use chrono::{DateTime, Local};
+
+/// how far a calculated cycle may be off a fetched one, the api rounds some of its times
+const TOLERANCE_SECS: i64 = 2 * 60;
+
+/// Cetus day starts every 150 minutes, 63 minutes after each multiple of 150 minutes since the
+/// unix epoch
+const CETUS: Phases = Phases {
+    anchor: 3_780,
+    phases: &[("day", 100 * 60), ("night", 50 * 60)],
+};
+
+const CAMBION: Phases = Phases {
+    anchor: CETUS.anchor,
+    phases: &[("fass", 100 * 60), ("vome", 50 * 60)],
+};
+
+const VALLIS: Phases = Phases {
+    anchor: 1_541_837_628,
+    phases: &[("warm", 400), ("cold", 1_200)],
+};
+
+const EARTH: Phases = Phases {
+    anchor: 0,
+    phases: &[("day", 4 * 60 * 60), ("night", 4 * 60 * 60)],
+};
+
+const ZARIMAN: Phases = Phases {
+    anchor: CETUS.anchor,
+    phases: &[("corpus", 150 * 60), ("grineer", 150 * 60)],
+};
+
+const DUVIRI: Phases = Phases {
+    anchor: 0,
+    phases: &[
+        ("sorrow", 2 * 60 * 60),
+        ("fear", 2 * 60 * 60),
+        ("joy", 2 * 60 * 60),
+        ("anger", 2 * 60 * 60),
+        ("envy", 2 * 60 * 60),
+    ],
+};
+
+///
+/// A cycle looping through `phases` (state and length in seconds), the first one starting at
+/// `anchor` seconds since the unix epoch
+///
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Phases {
+    anchor: i64,
+    phases: &'static [(&'static str, i64)],
+}
+
+///
+/// The phase a cycle is in at an instant
+///
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Phase {
+    pub(crate) state: &'static str,
+    pub(crate) next_state: &'static str,
+    pub(crate) activation: DateTime<Local>,
+    pub(crate) expiry: DateTime<Local>,
+}
+
+impl Phases {
+    fn period(&self) -> i64 {
+        self.phases.iter().map(|(_, len)| len).sum()
+    }
+
+    pub(crate) fn at(&self, instant: DateTime<Local>) -> Phase {
+        let millis = instant.timestamp_millis();
+        let period = self.period() * 1000;
+        let offset = (millis - self.anchor * 1000).rem_euclid(period);
+        let mut start = millis - offset;
+        for (i, (state, len)) in self.phases.iter().enumerate() {
+            let end = start + len * 1000;
+            if millis < end {
+                let (next_state, _) = self.phases[(i + 1) % self.phases.len()];
+                return Phase {
+                    state,
+                    next_state,
+                    activation: from_millis(start),
+                    expiry: from_millis(end),
+                };
+            }
+            start = end;
+        }
+        unreachable!("the offset is smaller than the period")
+    }
+}
+
+fn from_millis(millis: i64) -> DateTime<Local> {
+    DateTime::from_timestamp_millis(millis)
+        .expect("cycle times are in range")
+        .with_timezone(&Local)
+}
+
+///
+/// An open world cycle that can be calculated offline
+///
+pub trait OpenWorldCycle: Sized {
+    ///
+    /// The cycle as it is at `instant`, `time_left` is relative to `instant` too
+    ///
+    fn at(instant: DateTime<Local>) -> Self;
+
+    ///
+    /// The cycle as it is right now
+    ///
+    fn now() -> Self {
+        Self::at(Local::now())
+    }
+
+    fn state(&self) -> &str;
+
+    fn activation(&self) -> DateTime<Local>;
+
+    fn expiry(&self) -> DateTime<Local>;
+
+    ///
+    /// Whether the calculation agrees with this cycle fetched from the api, i.e. the same state
+    /// with activation and expiry at most a few minutes apart
+    ///
+    fn validate(&self) -> bool {
+        let middle = self.activation() + (self.expiry() - self.activation()) / 2;
+        let calculated = Self::at(middle);
+        let close =
+            |a: DateTime<Local>, b: DateTime<Local>| (a - b).num_seconds().abs() <= TOLERANCE_SECS;
+        calculated.state().eq_ignore_ascii_case(self.state())
+            && close(calculated.activation(), self.activation())
+            && close(calculated.expiry(), self.expiry())
+    }
+}
+
+///
+/// Implements the accessors of `OpenWorldCycle` for a cycle with `state`, `activation` and
+/// `expiry` fields
+///
+macro_rules! impl_cycle_accessors {
+    () => {
+        fn state(&self) -> &str {
+            &self.state
+        }
+
+        fn activation(&self) -> DateTime<Local> {
+            self.activation
+        }
+
+        fn expiry(&self) -> DateTime<Local> {
+            self.expiry
+        }
+    };
+}
+
+impl OpenWorldCycle for CetusCycle {
+    fn at(instant: DateTime<Local>) -> Self {
+        let phase = CETUS.at(instant);
+        CetusCycle {
+            id: format!("cetusCycle{}", phase.expiry.timestamp_millis()),
+            is_day: phase.state == "day",
+            state: phase.state.to_string(),
+            time_left: time_left(instant, phase.expiry),
+            is_cetus: true,
+            short_string: short_string(instant, &phase, capitalize(phase.next_state)),
+            expiry: phase.expiry,
+            activation: phase.activation,
+        }
+    }
+
+    impl_cycle_accessors!();
+}
+
+impl OpenWorldCycle for CambionCycle {
+    fn at(instant: DateTime<Local>) -> Self {
+        let phase = CAMBION.at(instant);
+        CambionCycle {
+            id: format!("cambionCycle{}", phase.expiry.timestamp_millis()),
+            time_left: time_left(instant, phase.expiry),
+            state: phase.state.to_string(),
+            active: phase.state.to_string(),
+            activation: phase.activation,
+            expiry: phase.expiry,
+        }
+    }
+
+    impl_cycle_accessors!();
+}
+
+impl OpenWorldCycle for VallisCycle {
+    fn at(instant: DateTime<Local>) -> Self {
+        let phase = VALLIS.at(instant);
+        VallisCycle {
+            id: format!("vallisCycle{}", phase.activation.timestamp_millis()),
+            is_warm: phase.state == "warm",
+            state: phase.state.to_string(),
+            time_left: time_left(instant, phase.expiry),
+            short_string: short_string(instant, &phase, capitalize(phase.next_state)),
+            expiry: phase.expiry,
+            activation: phase.activation,
+        }
+    }
+
+    impl_cycle_accessors!();
+}
+
+impl OpenWorldCycle for EarthCycle {
+    fn at(instant: DateTime<Local>) -> Self {
+        let phase = EARTH.at(instant);
+        EarthCycle {
+            id: format!("earthCycle{}", phase.expiry.timestamp_millis()),
+            is_day: phase.state == "day",
+            state: phase.state.to_string(),
+            time_left: time_left(instant, phase.expiry),
+            expiry: phase.expiry,
+            activation: phase.activation,
+        }
+    }
+
+    impl_cycle_accessors!();
+}
+
+impl OpenWorldCycle for ZarimanCycle {
+    fn at(instant: DateTime<Local>) -> Self {
+        let phase = ZARIMAN.at(instant);
+        ZarimanCycle {
+            id: format!("zarimanCycle{}", phase.expiry.timestamp_millis()),
+            is_corpus: phase.state == "corpus",
+            state: phase.state.to_string(),
+            time_left: time_left(instant, phase.expiry),
+            short_string: short_string(instant, &phase, phase.next_state.to_string()),
+            expiry: phase.expiry,
+            activation: phase.activation,
+        }
+    }
+
+    impl_cycle_accessors!();
+}
+
+impl OpenWorldCycle for DuviriCycle {
+    ///
+    /// The choices of the Circuit can't be calculated and are left empty
+    ///
+    fn at(instant: DateTime<Local>) -> Self {
+        let phase = DUVIRI.at(instant);
+        DuviriCycle {
+            id: format!(
+                "duviriCycle{}{}",
+                phase.state,
+                phase.expiry.timestamp_millis()
+            ),
+            state: phase.state.to_string(),
+            activation: phase.activation,
+            expiry: phase.expiry,
+            choices: Vec::new(),
+        }
+    }
+
+    impl_cycle_accessors!();
+}
+
+///
+/// Time from `instant` until `expiry` like the api formats it, e.g. "1h 19m 9s"
+///
+pub(crate) fn time_left(instant: DateTime<Local>, expiry: DateTime<Local>) -> String {
+    let secs = (expiry - instant).num_seconds().max(0);
+    let parts = [
+        (secs / 86_400, "d"),
+        (secs / 3_600 % 24, "h"),
+        (secs / 60 % 60, "m"),
+        (secs % 60, "s"),
+    ];
+    let text: Vec<_> = parts
+        .iter()
+        .filter(|(value, _)| *value > 0)
+        .map(|(value, unit)| format!("{}{}", value, unit))
+        .collect();
+    match text.is_empty() {
+        true => "0s".to_string(),
+        false => text.join(" "),
+    }
+}
+
+///
+/// e.g. "28m to Night", the time left without seconds
+///
+fn short_string(instant: DateTime<Local>, phase: &Phase, next_state: String) -> String {
+    let time_left = time_left(instant, phase.expiry);
+    let without_secs: Vec<_> = time_left
+        .split(' ')
+        .filter(|part| !part.ends_with('s'))
+        .collect();
+    let time_left = match without_secs.is_empty() {
+        true => "0m".to_string(),
+        false => without_secs.join(" "),
+    };
+    format!("{} to {}", time_left, next_state)
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    chars
+        .next()
+        .map(|first| first.to_uppercase().chain(chars).collect())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::OpenWorldCycle;
+    use crate::model::*;
+    use chrono::{DateTime, Local};
+    use std::fs;
+    use std::path::PathBuf;
+
+    #[test]
+    pub fn test_calculated_cycles() -> Result<(), Box<dyn std::error::Error>> {
+        let mut test_json = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_json.push("resources/test/pc_test.json");
+        let platform: Platform = serde_json::from_str(&fs::read_to_string(test_json)?)?;
+        assert!(platform.cetus_cycle.validate());
+        assert!(platform.cambion_cycle.validate());
+        assert!(platform.vallis_cycle.validate());
+        assert!(platform.earth_cycle.validate());
+        assert!(platform.zariman_cycle.validate());
+        assert!(platform.duviri_cycle.validate());
+
+        let fetched_at: DateTime<Local> = "2024-03-18T02:14:01Z".parse()?;
+        let cetus = CetusCycle::at(fetched_at);
+        assert_eq!(cetus, platform.cetus_cycle);
+        let night = CetusCycle::at(cetus.expiry);
+        assert_eq!(night.state, "night");
+        assert_eq!(night.short_string, "50m to Day");
+        assert!(night.validate());
+
+        let mut wrong = platform.vallis_cycle.clone();
+        wrong.state = "warm".to_string();
+        assert!(!wrong.validate());
+        Ok(())
+    }
+}
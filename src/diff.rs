@@ -0,0 +1,188 @@
+//!
+//! Typed change events between two snapshots of the worldstate of a platform
+//!
+use crate::model::*;
+
+///
+/// Something that changed between two worldstates, see `diff`
+///
+/// Events carry the entity as found in the newer worldstate, or in the older one if it's gone
+///
+#[derive(Debug, Clone, PartialEq)]
+pub enum WorldstateEvent {
+    FissureStarted(Box<Fissure>),
+    FissureEnded(Box<Fissure>),
+    AlertStarted(Box<Alert>),
+    AlertEnded(Box<Alert>),
+    InvasionStarted(Box<Invasion>),
+    InvasionEnded(Box<Invasion>),
+    /// The completion of an invasion moved, `previous_completion` is the older value
+    InvasionProgressed {
+        invasion: Box<Invasion>,
+        previous_completion: f64,
+    },
+    EventStarted(Box<Event>),
+    EventEnded(Box<Event>),
+    VoidTraderArrived(Box<VoidTrader>),
+    VoidTraderDeparted(Box<VoidTrader>),
+    SortieRotated(Box<Sortie>),
+    ArchonHuntRotated(Box<ArchonHunt>),
+    /// Cetus switched between day and night
+    CetusCycleChanged(Box<CetusCycle>),
+    /// The Orb Vallis switched between warm and cold
+    VallisCycleChanged(Box<VallisCycle>),
+    /// The Cambion Drift switched between fass and vome
+    CambionCycleChanged(Box<CambionCycle>),
+    /// The Zariman switched between corpus and grineer
+    ZarimanCycleChanged(Box<ZarimanCycle>),
+    EarthCycleChanged(Box<EarthCycle>),
+    DuviriCycleChanged(Box<DuviriCycle>),
+    /// Darvo offers a new deal
+    DailyDealChanged(Box<DailyDeal>),
+}
+
+///
+/// Compare two worldstates of the same platform and list what changed from `old` to `new`
+///
+/// Fissures, alerts, invasions, events and daily deals are matched by their `id`
+///
+pub fn diff(old: &Platform, new: &Platform) -> Vec<WorldstateEvent> {
+    use WorldstateEvent::*;
+
+    let mut events = Vec::new();
+    let (started, ended) = by_id(&old.fissures, &new.fissures, |f| &f.id);
+    events.extend(started.map(|f| FissureStarted(Box::new(f.clone()))));
+    events.extend(ended.map(|f| FissureEnded(Box::new(f.clone()))));
+
+    let (started, ended) = by_id(&old.alerts, &new.alerts, |a| &a.id);
+    events.extend(started.map(|a| AlertStarted(Box::new(a.clone()))));
+    events.extend(ended.map(|a| AlertEnded(Box::new(a.clone()))));
+
+    let (started, ended) = by_id(&old.invasions, &new.invasions, |i| &i.id);
+    events.extend(started.map(|i| InvasionStarted(Box::new(i.clone()))));
+    events.extend(ended.map(|i| InvasionEnded(Box::new(i.clone()))));
+    for invasion in &new.invasions {
+        let previous = old.invasions.iter().find(|i| i.id == invasion.id);
+        if let Some(previous) = previous.filter(|i| i.completion != invasion.completion) {
+            events.push(InvasionProgressed {
+                invasion: Box::new(invasion.clone()),
+                previous_completion: previous.completion,
+            });
+        }
+    }
+
+    let (started, ended) = by_id(&old.events, &new.events, |e| &e.id);
+    events.extend(started.map(|e| EventStarted(Box::new(e.clone()))));
+    events.extend(ended.map(|e| EventEnded(Box::new(e.clone()))));
+
+    match (old.void_trader.active, new.void_trader.active) {
+        (false, true) => events.push(VoidTraderArrived(Box::new(new.void_trader.clone()))),
+        (true, false) => events.push(VoidTraderDeparted(Box::new(old.void_trader.clone()))),
+        _ => {}
+    }
+
+    if old.sortie.id != new.sortie.id {
+        events.push(SortieRotated(Box::new(new.sortie.clone())));
+    }
+    if old.archon_hunt.id != new.archon_hunt.id {
+        events.push(ArchonHuntRotated(Box::new(new.archon_hunt.clone())));
+    }
+
+    if old.cetus_cycle.state != new.cetus_cycle.state {
+        events.push(CetusCycleChanged(Box::new(new.cetus_cycle.clone())));
+    }
+    if old.vallis_cycle.state != new.vallis_cycle.state {
+        events.push(VallisCycleChanged(Box::new(new.vallis_cycle.clone())));
+    }
+    if old.cambion_cycle.state != new.cambion_cycle.state {
+        events.push(CambionCycleChanged(Box::new(new.cambion_cycle.clone())));
+    }
+    if old.zariman_cycle.state != new.zariman_cycle.state {
+        events.push(ZarimanCycleChanged(Box::new(new.zariman_cycle.clone())));
+    }
+    if old.earth_cycle.state != new.earth_cycle.state {
+        events.push(EarthCycleChanged(Box::new(new.earth_cycle.clone())));
+    }
+    if old.duviri_cycle.state != new.duviri_cycle.state {
+        events.push(DuviriCycleChanged(Box::new(new.duviri_cycle.clone())));
+    }
+
+    let (started, _) = by_id(&old.daily_deals, &new.daily_deals, |d| &d.id);
+    events.extend(started.map(|d| DailyDealChanged(Box::new(d.clone()))));
+    events
+}
+
+impl Platform {
+    ///
+    /// What changed from `self` to the newer worldstate `new`, see `diff`
+    ///
+    pub fn diff(&self, new: &Platform) -> Vec<WorldstateEvent> {
+        diff(self, new)
+    }
+}
+
+///
+/// Entries only in `new` and entries only in `old`
+///
+fn by_id<'a, T>(
+    old: &'a [T],
+    new: &'a [T],
+    id: impl Fn(&T) -> &str + Copy + 'a,
+) -> (
+    impl Iterator<Item = &'a T> + 'a,
+    impl Iterator<Item = &'a T> + 'a,
+) {
+    let contains = move |items: &'a [T], item: &T| items.iter().any(|i| id(i) == id(item));
+    let started = new.iter().filter(move |item| !contains(old, item));
+    let ended = old.iter().filter(move |item| !contains(new, item));
+    (started, ended)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::WorldstateEvent;
+    use crate::model::*;
+    use std::fs;
+    use std::path::PathBuf;
+
+    #[test]
+    pub fn test_diff() -> Result<(), Box<dyn std::error::Error>> {
+        let mut test_json = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_json.push("resources/test/pc_test.json");
+        let old: Platform = serde_json::from_str(&fs::read_to_string(test_json)?)?;
+        assert!(old.diff(&old).is_empty());
+
+        let mut new = old.clone();
+        let ended = new.fissures.remove(0);
+        let mut started = ended.clone();
+        started.id = "new fissure".to_string();
+        new.fissures.push(started.clone());
+        new.invasions[0].completion += 1.0;
+        new.cetus_cycle.state = "night".to_string();
+        new.void_trader.active = true;
+        new.sortie.id = "new sortie".to_string();
+
+        let events = old.diff(&new);
+        assert!(events.contains(&WorldstateEvent::FissureStarted(Box::new(started))));
+        assert!(events.contains(&WorldstateEvent::FissureEnded(Box::new(ended))));
+        assert!(events.contains(&WorldstateEvent::InvasionProgressed {
+            invasion: Box::new(new.invasions[0].clone()),
+            previous_completion: old.invasions[0].completion,
+        }));
+        assert!(
+            events.contains(&WorldstateEvent::CetusCycleChanged(Box::new(
+                new.cetus_cycle.clone()
+            )))
+        );
+        assert!(
+            events.contains(&WorldstateEvent::VoidTraderArrived(Box::new(
+                new.void_trader.clone()
+            )))
+        );
+        assert!(events.contains(&WorldstateEvent::SortieRotated(Box::new(
+            new.sortie.clone()
+        ))));
+        assert_eq!(events.len(), 6);
+        Ok(())
+    }
+}
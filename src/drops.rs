@@ -0,0 +1,219 @@
+//!
+//! Drop tables: where items drop, how rare they are and at which chance
+//!
+//! `DropIndex` answers "where does X drop" offline from a saved drops json
+//!
+use crate::error::{self, from_json, WarframeError};
+use crate::model::{Job, Language, Reward};
+use crate::transport::Transport;
+use crate::WarframeClient;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+
+///
+/// An item dropping at a place, e.g. "Ash Prime Systems" from "Lith A1 Relic (Intact)"
+///
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DropEntry {
+    pub item: String,
+    pub place: String,
+    pub rarity: String,
+    /// chance in percent
+    pub chance: f64,
+}
+
+///
+/// All drops indexed by item name
+///
+/// Item names are compared ignoring case, spacing and quantities, so "3X Detonite Injector"
+/// finds the drops of "Detonite Injector"
+///
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct DropIndex {
+    drops: Vec<DropEntry>,
+    by_item: HashMap<String, Vec<usize>>,
+}
+
+impl DropIndex {
+    pub fn new(drops: Vec<DropEntry>) -> Self {
+        let mut by_item: HashMap<_, Vec<_>> = HashMap::new();
+        for (i, drop) in drops.iter().enumerate() {
+            by_item.entry(normalize(&drop.item)).or_default().push(i);
+        }
+        Self { drops, by_item }
+    }
+
+    ///
+    /// Load an index from a json file of drops, like the response of `/drops`
+    ///
+    pub async fn load(path: impl AsRef<Path>) -> error::Result<Self> {
+        let json = tokio::fs::read_to_string(path)
+            .await
+            .map_err(|err| WarframeError::Io(Arc::new(err)))?;
+        Ok(Self::new(from_json(&json)?))
+    }
+
+    ///
+    /// Save the drops as json, so they can be loaded with `DropIndex::load`
+    ///
+    pub async fn save(&self, path: impl AsRef<Path>) -> error::Result<()> {
+        let json = serde_json::to_vec(&self.drops)
+            .map_err(|err| WarframeError::Io(Arc::new(err.into())))?;
+        tokio::fs::write(path, json)
+            .await
+            .map_err(|err| WarframeError::Io(Arc::new(err)))
+    }
+
+    pub fn drops(&self) -> &[DropEntry] {
+        &self.drops
+    }
+
+    ///
+    /// Every drop of the item `name`
+    ///
+    pub fn for_item(&self, name: &str) -> Vec<&DropEntry> {
+        self.by_item
+            .get(&normalize(name))
+            .into_iter()
+            .flatten()
+            .map(|&i| &self.drops[i])
+            .collect()
+    }
+
+    ///
+    /// Every drop whose item or place contains `query`, ignoring case
+    ///
+    pub fn search(&self, query: &str) -> Vec<&DropEntry> {
+        let query = query.trim().to_lowercase();
+        self.drops
+            .iter()
+            .filter(|drop| {
+                drop.item.to_lowercase().contains(&query)
+                    || drop.place.to_lowercase().contains(&query)
+            })
+            .collect()
+    }
+
+    ///
+    /// Every drop of the items of a reward, e.g. `Mission.reward` or an invasion reward
+    ///
+    pub fn for_reward(&self, reward: &Reward) -> Vec<&DropEntry> {
+        let counted = reward.counted_items.iter().map(|item| &item.type_field);
+        reward
+            .items
+            .iter()
+            .chain(counted)
+            .flat_map(|item| self.for_item(item))
+            .collect()
+    }
+
+    ///
+    /// Every drop of the items in the reward pool of a bounty
+    ///
+    pub fn for_job(&self, job: &Job) -> Vec<&DropEntry> {
+        job.reward_pool
+            .iter()
+            .flat_map(|item| self.for_item(item))
+            .collect()
+    }
+}
+
+impl From<Vec<DropEntry>> for DropIndex {
+    fn from(drops: Vec<DropEntry>) -> Self {
+        Self::new(drops)
+    }
+}
+
+///
+/// Lowercase `name` with single spaces and without leading quantities like "3X " or "1,500 "
+///
+fn normalize(name: &str) -> String {
+    let mut words: Vec<_> = name.split_whitespace().collect();
+    let is_quantity = |word: &str| {
+        let digits = word.trim_end_matches(['x', 'X']);
+        !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit() || c == ',')
+    };
+    while words.len() > 1 && is_quantity(words[0]) {
+        words.remove(0);
+    }
+    words.join(" ").to_lowercase()
+}
+
+impl<T: Transport> WarframeClient<T> {
+    ///
+    /// Get every drop, cached like the item database
+    ///
+    pub async fn get_drops(
+        &self,
+        lang: impl Into<Option<Language>>,
+    ) -> error::Result<Vec<DropEntry>> {
+        self.get_static(&["drops"], &[], lang).await
+    }
+
+    ///
+    /// Get every drop whose item or place contains `query`, ignoring case
+    ///
+    pub async fn search_drops(
+        &self,
+        query: &str,
+        lang: impl Into<Option<Language>>,
+    ) -> error::Result<Vec<DropEntry>> {
+        self.get_static(&["drops", "search", query], &[], lang)
+            .await
+    }
+
+    ///
+    /// Get every drop as an index for offline lookups
+    ///
+    pub async fn get_drop_index(
+        &self,
+        lang: impl Into<Option<Language>>,
+    ) -> error::Result<DropIndex> {
+        self.get_drops(lang).await.map(DropIndex::new)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DropIndex;
+    use crate::model::*;
+    use crate::transport::FixtureTransport;
+    use crate::WarframeClient;
+    use std::fs;
+    use std::path::PathBuf;
+
+    #[tokio::test]
+    pub async fn test_drop_index() -> Result<(), Box<dyn std::error::Error>> {
+        let mut drops_json = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        drops_json.push("resources/test/drops_test.json");
+        let index = DropIndex::load(&drops_json).await?;
+        assert_eq!(index.for_item("ash prime  SYSTEMS").len(), 2);
+        assert_eq!(index.search("sedna/hydron")[0].item, "Axi A1 Relic");
+
+        let mut test_json = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_json.push("resources/test/pc_test.json");
+        let platform: Platform = serde_json::from_str(&fs::read_to_string(test_json)?)?;
+        let ostrons = platform
+            .syndicate_missions
+            .iter()
+            .find(|mission| mission.syndicate == "Ostrons")
+            .unwrap();
+        let plastids = index.for_job(&ostrons.jobs[0]);
+        assert!(plastids.iter().any(|drop| drop.item == "200X Plastids"));
+        assert!(plastids.iter().any(|drop| drop.item == "Vitality"));
+        let dera = index.for_reward(&platform.invasions[2].attacker.reward.clone().unwrap());
+        assert_eq!(dera[0].place, "Invasion Reward");
+
+        let fixtures =
+            FixtureTransport::new().with_json("/drops", fs::read_to_string(&drops_json)?);
+        let client = WarframeClient::builder().build_with_transport(fixtures)?;
+        let dir = tempfile::tempdir()?;
+        let saved = dir.path().join("drops.json");
+        client.get_drop_index(None).await?.save(&saved).await?;
+        assert_eq!(DropIndex::load(&saved).await?, index);
+        Ok(())
+    }
+}
@@ -0,0 +1,122 @@
+use crate::model::PlatformType;
+use reqwest::StatusCode;
+use std::sync::Arc;
+use std::time::Duration;
+use thiserror::Error;
+
+///
+/// Result type of every fallible operation of this crate
+///
+pub type Result<T> = std::result::Result<T, WarframeError>;
+
+///
+/// Everything that can go wrong while talking to the Warframestat API
+///
+/// Cheap to clone, so the same error can be handed to every caller waiting on a request
+///
+#[derive(Debug, Clone, Error)]
+pub enum WarframeError {
+    /// The request couldn't be sent or the response couldn't be read, e.g. a timeout or a
+    /// refused connection
+    #[error("transport error: {0}")]
+    Transport(#[source] Arc<reqwest::Error>),
+    /// A custom `Transport` couldn't complete the request, `kind` tells whether retrying might
+    /// help, see `WarframeError::custom_transport`
+    #[error("transport error ({kind:?}): {source}")]
+    CustomTransport {
+        kind: TransportErrorKind,
+        #[source]
+        source: Arc<dyn std::error::Error + Send + Sync>,
+    },
+    /// The API answered with a non-success status code
+    #[error("api responded with {status}: {body}")]
+    Status { status: StatusCode, body: String },
+    /// The API answered with `404 Not Found`, e.g. for an unknown platform
+    #[error("nothing found at {url}")]
+    NotFound { url: String },
+    /// There's no player called `username` on `platform`
+    #[error("no player {username} on {platform}")]
+    PlayerNotFound {
+        username: String,
+        platform: PlatformType,
+    },
+    /// The API answered with `429 Too Many Requests`
+    #[error("rate limited by the api, retry after {retry_after:?}")]
+    RateLimited { retry_after: Option<Duration> },
+    /// The response didn't match the model, `path` points to the field that failed
+    #[error("couldn't deserialize `{path}`: {source}")]
+    Deserialize {
+        path: String,
+        #[source]
+        source: Arc<serde_json::Error>,
+    },
+    /// The configured url isn't valid
+    #[error("invalid url: {0}")]
+    InvalidUrl(#[from] url::ParseError),
+    /// The cache couldn't be read or written
+    #[error("cache error: {0}")]
+    Cache(String),
+    /// The client serves a snapshot and `url` isn't part of it, e.g. the item database or a
+    /// player profile
+    #[error("{url} isn't available in snapshot mode")]
+    NotInSnapshot { url: String },
+    /// A local file couldn't be read or written, e.g. a snapshot
+    #[error("io error: {0}")]
+    Io(#[source] Arc<std::io::Error>),
+}
+
+///
+/// Why a custom `Transport` couldn't complete a request
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransportErrorKind {
+    /// the request timed out, it's retried
+    Timeout,
+    /// no connection could be established, it's retried
+    Connect,
+    /// anything else, it isn't retried
+    Other,
+}
+
+impl WarframeError {
+    ///
+    /// The error of a custom `Transport` that couldn't complete a request, e.g. a local stand-in
+    /// that refused the connection
+    ///
+    pub fn custom_transport(
+        kind: TransportErrorKind,
+        source: impl Into<Box<dyn std::error::Error + Send + Sync>>,
+    ) -> Self {
+        WarframeError::CustomTransport {
+            kind,
+            source: Arc::from(source.into()),
+        }
+    }
+}
+
+impl From<reqwest::Error> for WarframeError {
+    fn from(err: reqwest::Error) -> Self {
+        WarframeError::Transport(Arc::new(err))
+    }
+}
+
+///
+/// Deserialize `json` into `T`, remembering the json path that failed
+///
+pub(crate) fn from_json<T: serde::de::DeserializeOwned>(json: &str) -> Result<T> {
+    let de = &mut serde_json::Deserializer::from_str(json);
+    serde_path_to_error::deserialize(de).map_err(|err| WarframeError::Deserialize {
+        path: err.path().to_string(),
+        source: Arc::new(err.into_inner()),
+    })
+}
+
+///
+/// Deserialize an already parsed json `value` into `T`, remembering the json path that failed
+///
+pub(crate) fn from_value<T: serde::de::DeserializeOwned>(value: &serde_json::Value) -> Result<T> {
+    serde_path_to_error::deserialize(value).map_err(|err| WarframeError::Deserialize {
+        path: err.path().to_string(),
+        source: Arc::new(err.into_inner()),
+    })
+}
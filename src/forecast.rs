@@ -0,0 +1,155 @@
+//!
+//! Upcoming windows of the open world cycles, e.g. the next Cetus nights, as intervals or as an
+//! iCalendar
+//!
+use crate::cycles::OpenWorldCycle;
+use chrono::{DateTime, Local, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+
+/// the most phases in a row that can differ from a state, Duviri has five states
+const MAX_MISSES: usize = 8;
+
+///
+/// A span of time a cycle is in `state`
+///
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Window {
+    pub state: String,
+    pub activation: DateTime<Local>,
+    pub expiry: DateTime<Local>,
+}
+
+impl Window {
+    ///
+    /// The window as an iCalendar `VEVENT` called `summary`
+    ///
+    pub fn to_ical_event(&self, summary: &str) -> String {
+        let format = |time: DateTime<Local>| time.with_timezone(&Utc).format("%Y%m%dT%H%M%SZ");
+        let mut event = String::new();
+        let lines = [
+            "BEGIN:VEVENT".to_string(),
+            format!(
+                "UID:{}-{}@warframestat-rs",
+                self.state,
+                self.activation.timestamp()
+            ),
+            format!("DTSTAMP:{}", format(Local::now())),
+            format!("DTSTART:{}", format(self.activation)),
+            format!("DTEND:{}", format(self.expiry)),
+            format!("SUMMARY:{}", escape(summary)),
+            "END:VEVENT".to_string(),
+        ];
+        for line in lines {
+            // iCalendar lines end with CRLF
+            let _ = write!(event, "{}\r\n", line);
+        }
+        event
+    }
+}
+
+///
+/// The next `count` windows in which the cycle `C` is in `state`, starting with the current one if
+/// it's in `state` at `from`
+///
+/// Returns no windows if the cycle has no such state
+///
+/// ```
+/// use warframestat_rs::forecast::forecast;
+/// use warframestat_rs::model::CetusCycle;
+///
+/// let nights = forecast::<CetusCycle>("night", chrono::Local::now(), 5);
+/// assert_eq!(nights.len(), 5);
+/// ```
+///
+pub fn forecast<C: OpenWorldCycle>(
+    state: &str,
+    from: DateTime<Local>,
+    count: usize,
+) -> Vec<Window> {
+    let mut windows = Vec::with_capacity(count);
+    let mut instant = from;
+    let mut misses = 0;
+    while windows.len() < count && misses <= MAX_MISSES {
+        let cycle = C::at(instant);
+        if cycle.state().eq_ignore_ascii_case(state.trim()) {
+            windows.push(Window {
+                state: cycle.state().to_string(),
+                activation: cycle.activation(),
+                expiry: cycle.expiry(),
+            });
+            misses = 0;
+        } else {
+            misses += 1;
+        }
+        instant = cycle.expiry();
+    }
+    windows
+}
+
+///
+/// An iCalendar with an event called `summary` for every window, e.g. to import upcoming Cetus
+/// nights into a calendar app
+///
+pub fn to_ical(windows: &[Window], summary: &str) -> String {
+    let mut calendar = String::from(
+        "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nPRODID:-//warframestat-rs//cycle forecast//EN\r\n",
+    );
+    for window in windows {
+        calendar.push_str(&window.to_ical_event(summary));
+    }
+    calendar.push_str("END:VCALENDAR\r\n");
+    calendar
+}
+
+///
+/// Escape text for an iCalendar property value
+///
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{forecast, to_ical};
+    use crate::model::*;
+    use chrono::{DateTime, Duration, Local};
+
+    #[test]
+    pub fn test_forecast() -> Result<(), Box<dyn std::error::Error>> {
+        let from: DateTime<Local> = "2024-03-18T02:14:01Z".parse()?;
+        let nights = forecast::<CetusCycle>("Night", from, 5);
+        assert_eq!(nights.len(), 5);
+        assert_eq!(
+            nights[0].activation,
+            "2024-03-18T02:43:00Z".parse::<DateTime<Local>>()?
+        );
+        for (night, next) in nights.iter().zip(&nights[1..]) {
+            assert_eq!(night.expiry - night.activation, Duration::minutes(50));
+            assert_eq!(next.activation - night.activation, Duration::minutes(150));
+        }
+
+        let days = forecast::<CetusCycle>("day", from, 1);
+        assert_eq!(
+            days[0].activation,
+            "2024-03-18T01:03:00Z".parse::<DateTime<Local>>()?
+        );
+        let warm = forecast::<VallisCycle>("warm", from, 3);
+        assert!(warm
+            .iter()
+            .all(|w| w.expiry - w.activation == Duration::seconds(400)));
+        assert!(forecast::<DuviriCycle>("envy", from, 2).len() == 2);
+        assert!(forecast::<CetusCycle>("warm", from, 2).is_empty());
+
+        let calendar = to_ical(&nights, "Eidolons, Cetus night");
+        assert!(calendar.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert_eq!(calendar.matches("BEGIN:VEVENT").count(), 5);
+        assert!(calendar.contains("DTSTART:20240318T024300Z\r\nDTEND:20240318T033300Z\r\n"));
+        assert!(calendar.contains("SUMMARY:Eidolons\\, Cetus night\r\n"));
+        Ok(())
+    }
+}
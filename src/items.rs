@@ -0,0 +1,378 @@
+//!
+//! Typed models and getters for the static item database (`/items`, `/warframes`, ...)
+//!
+//! The item database only changes with game updates, so it's cached for
+//! `CachePolicy::static_ttl`. It isn't part of a snapshot, so it's not available in snapshot
+//! mode.
+//!
+//! Entries differ a lot between categories, so missing fields are defaulted
+//!
+use crate::error;
+use crate::model::Language;
+use crate::transport::Transport;
+use crate::WarframeClient;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Item {
+    pub unique_name: String,
+    pub name: String,
+    pub description: String,
+    #[serde(rename = "type")]
+    pub type_field: String,
+    pub category: String,
+    pub image_name: String,
+    pub wikia_url: Option<String>,
+    pub tradable: bool,
+    pub masterable: bool,
+    pub vaulted: Option<bool>,
+    pub release_date: Option<String>,
+    pub drops: Vec<ItemDrop>,
+    pub components: Vec<Component>,
+}
+
+///
+/// Where an item or component drops
+///
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ItemDrop {
+    pub location: String,
+    #[serde(rename = "type")]
+    pub type_field: String,
+    pub rarity: Option<String>,
+    pub chance: Option<f64>,
+}
+
+///
+/// A part needed to craft an item, e.g. a warframe's neuroptics
+///
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Component {
+    pub unique_name: String,
+    pub name: String,
+    pub description: String,
+    pub item_count: i64,
+    pub image_name: String,
+    pub tradable: bool,
+    pub ducats: Option<i64>,
+    pub drops: Vec<ItemDrop>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Warframe {
+    pub unique_name: String,
+    pub name: String,
+    pub description: String,
+    #[serde(rename = "type")]
+    pub type_field: String,
+    pub category: String,
+    pub product_category: String,
+    pub health: f64,
+    pub shield: f64,
+    pub armor: f64,
+    pub power: f64,
+    pub sprint_speed: f64,
+    pub mastery_req: i64,
+    pub passive_description: Option<String>,
+    pub aura: Option<String>,
+    pub polarities: Vec<String>,
+    pub abilities: Vec<Ability>,
+    pub is_prime: bool,
+    pub vaulted: Option<bool>,
+    pub image_name: String,
+    pub wikia_url: Option<String>,
+    pub tradable: bool,
+    pub masterable: bool,
+    pub release_date: Option<String>,
+    pub components: Vec<Component>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Ability {
+    pub unique_name: String,
+    pub name: String,
+    pub description: String,
+    pub image_name: Option<String>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Weapon {
+    pub unique_name: String,
+    pub name: String,
+    pub description: String,
+    #[serde(rename = "type")]
+    pub type_field: String,
+    pub category: String,
+    pub product_category: String,
+    pub slot: Option<i64>,
+    pub mastery_req: i64,
+    pub total_damage: f64,
+    /// damage per damage type, e.g. "impact"
+    pub damage: HashMap<String, f64>,
+    pub critical_chance: f64,
+    pub critical_multiplier: f64,
+    pub proc_chance: f64,
+    pub fire_rate: f64,
+    pub accuracy: Option<f64>,
+    pub magazine_size: Option<i64>,
+    pub reload_time: Option<f64>,
+    pub trigger: Option<String>,
+    pub noise: Option<String>,
+    /// riven disposition from 1 to 5
+    pub disposition: Option<i64>,
+    pub omega_attenuation: Option<f64>,
+    pub is_prime: bool,
+    pub vaulted: Option<bool>,
+    pub image_name: String,
+    pub wikia_url: Option<String>,
+    pub tradable: bool,
+    pub masterable: bool,
+    pub release_date: Option<String>,
+    pub components: Vec<Component>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Mod {
+    pub unique_name: String,
+    pub name: String,
+    pub description: Option<String>,
+    #[serde(rename = "type")]
+    pub type_field: String,
+    pub category: String,
+    pub polarity: String,
+    pub rarity: String,
+    pub base_drain: i64,
+    pub fusion_limit: i64,
+    /// what the mod can be equipped on, e.g. "Excalibur"
+    pub compat_name: Option<String>,
+    pub is_augment: bool,
+    pub is_exilus: bool,
+    pub is_utility: bool,
+    pub level_stats: Vec<LevelStats>,
+    pub drops: Vec<ItemDrop>,
+    pub image_name: String,
+    pub wikia_url: Option<String>,
+    pub tradable: bool,
+    pub transmutable: bool,
+}
+
+///
+/// The stats of a mod or arcane at one rank
+///
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct LevelStats {
+    pub stats: Vec<String>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Arcane {
+    pub unique_name: String,
+    pub name: String,
+    #[serde(rename = "type")]
+    pub type_field: String,
+    pub category: String,
+    pub rarity: Option<String>,
+    pub level_stats: Vec<LevelStats>,
+    pub drops: Vec<ItemDrop>,
+    pub image_name: String,
+    pub tradable: bool,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Relic {
+    pub unique_name: String,
+    pub name: String,
+    #[serde(rename = "type")]
+    pub type_field: String,
+    pub category: String,
+    pub vaulted: Option<bool>,
+    pub rewards: Vec<RelicReward>,
+    pub drops: Vec<ItemDrop>,
+    pub image_name: String,
+    pub tradable: bool,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RelicReward {
+    pub rarity: String,
+    pub chance: f64,
+    pub item: RelicItem,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RelicItem {
+    pub unique_name: String,
+    pub name: String,
+}
+
+impl<T: Transport> WarframeClient<T> {
+    ///
+    /// Get every item of the item database
+    ///
+    pub async fn get_items(&self, lang: impl Into<Option<Language>>) -> error::Result<Vec<Item>> {
+        self.get_static(&["items"], &[], lang).await
+    }
+
+    ///
+    /// Get every item whose name contains `name`, ignoring case
+    ///
+    pub async fn search_items(
+        &self,
+        name: &str,
+        lang: impl Into<Option<Language>>,
+    ) -> error::Result<Vec<Item>> {
+        self.get_static(&["items", "search", name], &[], lang).await
+    }
+
+    pub async fn get_warframes(
+        &self,
+        lang: impl Into<Option<Language>>,
+    ) -> error::Result<Vec<Warframe>> {
+        self.get_static(&["warframes"], &[], lang).await
+    }
+
+    ///
+    /// Get every warframe, archwing and necramech whose name contains `name`, ignoring case
+    ///
+    pub async fn search_warframes(
+        &self,
+        name: &str,
+        lang: impl Into<Option<Language>>,
+    ) -> error::Result<Vec<Warframe>> {
+        self.get_static(&["warframes", "search", name], &[], lang)
+            .await
+    }
+
+    pub async fn get_weapons(
+        &self,
+        lang: impl Into<Option<Language>>,
+    ) -> error::Result<Vec<Weapon>> {
+        self.get_static(&["weapons"], &[], lang).await
+    }
+
+    ///
+    /// Get every weapon whose name contains `name`, ignoring case
+    ///
+    pub async fn search_weapons(
+        &self,
+        name: &str,
+        lang: impl Into<Option<Language>>,
+    ) -> error::Result<Vec<Weapon>> {
+        self.get_static(&["weapons", "search", name], &[], lang)
+            .await
+    }
+
+    pub async fn get_mods(&self, lang: impl Into<Option<Language>>) -> error::Result<Vec<Mod>> {
+        self.get_static(&["mods"], &[], lang).await
+    }
+
+    ///
+    /// Get every mod whose name contains `name`, ignoring case
+    ///
+    pub async fn search_mods(
+        &self,
+        name: &str,
+        lang: impl Into<Option<Language>>,
+    ) -> error::Result<Vec<Mod>> {
+        self.get_static(&["mods", "search", name], &[], lang).await
+    }
+
+    pub async fn get_arcanes(
+        &self,
+        lang: impl Into<Option<Language>>,
+    ) -> error::Result<Vec<Arcane>> {
+        self.get_static(&["items", "search", "Arcanes"], &[("by", "category")], lang)
+            .await
+    }
+
+    ///
+    /// Get every arcane whose name contains `name`, ignoring case
+    ///
+    pub async fn search_arcanes(
+        &self,
+        name: &str,
+        lang: impl Into<Option<Language>>,
+    ) -> error::Result<Vec<Arcane>> {
+        let arcanes = self.get_arcanes(lang).await?;
+        Ok(filter_by_name(arcanes, name, |arcane| &arcane.name))
+    }
+
+    pub async fn get_relics(&self, lang: impl Into<Option<Language>>) -> error::Result<Vec<Relic>> {
+        self.get_static(&["items", "search", "Relics"], &[("by", "category")], lang)
+            .await
+    }
+
+    ///
+    /// Get every relic whose name contains `name`, ignoring case, e.g. "axi a1"
+    ///
+    pub async fn search_relics(
+        &self,
+        name: &str,
+        lang: impl Into<Option<Language>>,
+    ) -> error::Result<Vec<Relic>> {
+        let relics = self.get_relics(lang).await?;
+        Ok(filter_by_name(relics, name, |relic| &relic.name))
+    }
+}
+
+fn filter_by_name<I>(items: Vec<I>, name: &str, item_name: impl Fn(&I) -> &str) -> Vec<I> {
+    let name = name.trim().to_lowercase();
+    items
+        .into_iter()
+        .filter(|item| item_name(item).to_lowercase().contains(&name))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::model::*;
+    use crate::transport::FixtureTransport;
+    use crate::WarframeClient;
+    use std::fs;
+    use std::path::PathBuf;
+
+    #[tokio::test]
+    pub async fn test_item_search() -> Result<(), Box<dyn std::error::Error>> {
+        let mut test_json = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_json.push("resources/test/warframes_test.json");
+        let arcanes = r#"[
+            {"uniqueName": "/Lotus/Upgrades/CosmeticEnhancers/Utility/EnergyOnKill", "name": "Arcane Energize", "type": "Arcane", "category": "Arcanes", "rarity": "Legendary", "levelStats": [{"stats": ["+15% chance to restore energy"]}], "tradable": true},
+            {"uniqueName": "/Lotus/Upgrades/CosmeticEnhancers/Defensive/HealthOnDamage", "name": "Arcane Grace", "type": "Arcane", "category": "Arcanes", "tradable": true}
+        ]"#;
+        let fixtures = FixtureTransport::new()
+            .with_json(
+                "/warframes/search/excalibur%20prime",
+                fs::read_to_string(test_json)?,
+            )
+            .with_json("/items/search/Arcanes", arcanes);
+        let client = WarframeClient::builder().build_with_transport(fixtures.clone())?;
+
+        let warframes = client.search_warframes("excalibur prime", None).await?;
+        assert_eq!(warframes[0].name, "Excalibur Prime");
+        assert_eq!(warframes[0].abilities.len(), 2);
+        assert_eq!(warframes[0].components[0].name, "Neuroptics");
+        client.search_warframes("excalibur prime", None).await?;
+
+        let energize = client.search_arcanes("ENERGIZE", Language::German).await?;
+        assert_eq!(energize.len(), 1);
+        assert_eq!(energize[0].level_stats[0].stats.len(), 1);
+
+        let requests = fixtures.requests();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[1].0.query(), Some("language=de&by=category"));
+        Ok(())
+    }
+}
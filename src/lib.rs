@@ -1,183 +1,547 @@
+pub mod builder;
+pub mod cache;
+pub mod cycles;
+pub mod diff;
+pub mod drops;
+pub mod error;
+pub mod forecast;
+pub mod items;
+pub mod localized;
 pub mod model;
+pub mod nodes;
+pub mod profile;
+pub mod retry;
+pub mod rivens;
+pub mod rules;
+pub mod snapshot;
+pub mod transport;
+pub mod watch;
+pub mod webhook;
+pub use builder::WarframeClientBuilder;
+pub use cache::{CacheBackend, CacheEntry, CachePolicy, DiskBackend, MemoryBackend, WarframeCache};
+pub use cycles::OpenWorldCycle;
+pub use diff::WorldstateEvent;
+use error::from_json;
+pub use error::{TransportErrorKind, WarframeError};
+use localized::Localize;
 use model::*;
+pub use retry::RetryPolicy;
+use retry::{parse_retry_after, RetryEvent};
+pub use snapshot::Snapshot;
+pub use transport::{ReqwestTransport, Transport, TransportResponse};
+pub use watch::{WatchOptions, Watcher};
 
 use chrono::{DateTime, Local};
-use reqwest::{Client as HttpClient, Response};
+use futures::stream::{self, StreamExt};
+use reqwest::header::{
+    HeaderMap, HeaderName, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED, RETRY_AFTER,
+};
+use reqwest::StatusCode;
+use serde::de::DeserializeOwned;
 use std::collections::HashMap;
 use url::Url;
 
-use std::sync::Arc;
-use tokio::sync::Mutex;
-
-const API_URL: &'static str = "https://api-warframestat.us";
+const API_URL: &str = "https://api-warframestat.us";
 
 ///
 /// WarframeClient to request data from the [Warframestat API](https://doc.warframestat.us)
 ///
+/// Requests are sent with a `Transport`, reqwest by default
+///
 #[derive(Debug, Clone)]
-pub struct WarframeClient {
+pub struct WarframeClient<T: Transport = ReqwestTransport> {
     base_url: Url,
-    http: HttpClient,
-    cache: WarframeCache<&'static str>,
+    transport: T,
+    cache: WarframeCache<String>,
+    platform: PlatformType,
+    language: Language,
+    cache_policy: CachePolicy,
+    retry_policy: RetryPolicy,
+    snapshot: Option<Snapshot>,
+    concurrency: usize,
 }
 
 impl Default for WarframeClient {
     fn default() -> WarframeClient {
-        WarframeClient {
-            base_url: Url::parse(API_URL).expect(&format!("couldn't parse url from {}", API_URL)),
-            ..Default::default()
-        }
+        WarframeClientBuilder::default()
+            .build()
+            .unwrap_or_else(|err| panic!("couldn't build the default client: {}", err))
     }
 }
 
 ///
-/// defines a cache entry to validate cache entries based on the `timestamp`
+/// Calls `$callback` with the getter name, api path and type of every worldstate section of
+/// `model::Platform`
 ///
-#[derive(Debug, Clone)]
-pub struct CacheEntry<T>
-where
-    T: Clone,
-{
-    pub entry: T,
-    expiration_time: DateTime<Local>,
+macro_rules! for_each_section {
+    ($callback:ident) => {
+        $callback! {
+            /// Get the currently running alerts
+            get_alerts => "alerts": Vec<Alert>;
+            /// Get the currently open void fissures
+            get_fissures => "fissures": Vec<Fissure>;
+            /// Get the daily sortie
+            get_sortie => "sortie": Sortie;
+            /// Get the weekly archon hunt
+            get_archon_hunt => "archonHunt": ArchonHunt;
+            /// Get the ongoing invasions
+            get_invasions => "invasions": Vec<Invasion>;
+            /// Get Baro Ki'Teer, the void trader
+            get_void_trader => "voidTrader": VoidTrader;
+            /// Get Varzia, the prime resurgence vault trader
+            get_vault_trader => "vaultTrader": VaultTrader;
+            /// Get the current nightwave season and its challenges
+            get_nightwave => "nightwave": Nightwave;
+            /// Get the steel path honors rotation and incursions
+            get_steel_path => "steelPath": SteelPath;
+            /// Get the current arbitration
+            get_arbitration => "arbitration": Arbitration;
+            /// Get the current kuva siphon and flood missions
+            get_kuva => "kuva": Vec<Kuva>;
+            /// Get the day/night cycle of the Plains of Eidolon
+            get_cetus_cycle => "cetusCycle": CetusCycle;
+            /// Get the warm/cold cycle of the Orb Vallis
+            get_vallis_cycle => "vallisCycle": VallisCycle;
+            /// Get the fass/vome cycle of the Cambion Drift
+            get_cambion_cycle => "cambionCycle": CambionCycle;
+            /// Get the corpus/grineer cycle of the Zariman
+            get_zariman_cycle => "zarimanCycle": ZarimanCycle;
+            /// Get the current spiral of Duviri
+            get_duviri_cycle => "duviriCycle": DuviriCycle;
+            /// Get the day/night cycle of Earth
+            get_earth_cycle => "earthCycle": EarthCycle;
+            /// Get Darvo's daily deals
+            get_daily_deals => "dailyDeals": Vec<DailyDeal>;
+            /// Get the current market flash sales
+            get_flash_sales => "flashSales": Vec<FlashSale>;
+            /// Get the current conclave challenges
+            get_conclave_challenges => "conclaveChallenges": Vec<ConclaveChallenge>;
+            /// Get the syndicate missions and bounties
+            get_syndicate_missions => "syndicateMissions": Vec<SyndicateMission>;
+            /// Get the current sentient outpost
+            get_sentient_outposts => "sentientOutposts": SentientOutposts;
+            /// Get the construction progress of the fomorian and razorback
+            get_construction_progress => "constructionProgress": ConstructionProgress;
+            /// Get Cephalon Simaris' current synthesis target
+            get_simaris => "simaris": Simaris;
+            /// Get the currently running events
+            get_events => "events": Vec<Event>;
+            /// Get the in-game news
+            get_news => "news": Vec<News>;
+        }
+    };
 }
 
-impl<T: Clone> CacheEntry<T> {
-    fn is_expired(&self) -> bool {
-        Local::now() >= self.expiration_time
-    }
+///
+/// Calls `$callback` with the getter name, api path and type of every worldstate section that
+/// implements `localized::Localize`
+///
+macro_rules! for_each_localized_section {
+    ($callback:ident) => {
+        $callback! {
+            /// Get the currently open void fissures in every language of `langs`
+            get_localized_fissures => "fissures": Vec<Fissure>;
+            /// Get the currently running alerts in every language of `langs`
+            get_localized_alerts => "alerts": Vec<Alert>;
+            /// Get the ongoing invasions in every language of `langs`
+            get_localized_invasions => "invasions": Vec<Invasion>;
+            /// Get the daily sortie in every language of `langs`
+            get_localized_sortie => "sortie": Sortie;
+            /// Get the current arbitration in every language of `langs`
+            get_localized_arbitration => "arbitration": Arbitration;
+        }
+    };
 }
 
-#[derive(Debug, PartialEq, Eq, Hash)]
-struct CacheKey<T>
-where
-    T: std::hash::Hash + PartialEq + Eq,
-{
-    pub key_value: T,
-    pub language: Language,
-    pub platform: PlatformType,
+///
+/// Generates a getter for every localized section, see `for_each_localized_section`
+///
+macro_rules! localized_getters {
+    ($($(#[$meta:meta])* $name:ident => $path:literal: $ty:ty;)*) => {
+        $(
+            $(#[$meta])*
+            ///
+            /// `platform` falls back to the default of the client when `None`, `langs` to the
+            /// default language when empty
+            pub async fn $name(
+                &self,
+                platform: impl Into<Option<PlatformType>>,
+                langs: &[Language],
+            ) -> error::Result<<$ty as Localize>::Localized> {
+                self.get_localized_section::<$ty>($path, platform, langs).await
+            }
+        )*
+    };
 }
 
-impl<T: std::hash::Hash + PartialEq + Eq> CacheKey<T> {
-    fn new(key_value: T, language: Language, platform: PlatformType) -> Self {
-        Self {
-            key_value,
-            language,
-            platform,
-        }
-    }
+///
+/// Generates a cached getter for every worldstate section of `model::Platform`
+///
+macro_rules! section_getters {
+    ($($(#[$meta:meta])* $name:ident => $path:literal: $ty:ty;)*) => {
+        $(
+            $(#[$meta])*
+            pub async fn $name(
+                &self,
+                platform: impl Into<Option<PlatformType>>,
+                lang: impl Into<Option<Language>>,
+            ) -> error::Result<$ty> {
+                self.get_section($path, platform, lang).await
+            }
+        )*
+    };
 }
 
-impl<T: Clone> CacheEntry<T> {
-    fn new(entry: T, expiration_time: DateTime<Local>) -> Self {
-        Self {
-            entry,
-            expiration_time,
+#[cfg(feature = "blocking")]
+pub mod blocking;
+
+impl WarframeClient {
+    ///
+    /// Create new WarframeClient that can be reused for multiple requests
+    ///
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    ///
+    /// Configure a new WarframeClient, see `WarframeClientBuilder`
+    ///
+    pub fn builder() -> WarframeClientBuilder {
+        WarframeClientBuilder::default()
+    }
+
+    ///
+    /// Create a WarframeClient that serves every getter from the saved worldstates in `dir`
+    /// instead of the api, see `Snapshot`
+    ///
+    pub fn from_snapshot(dir: impl Into<std::path::PathBuf>) -> Self {
+        WarframeClient {
+            snapshot: Some(Snapshot::new(dir)),
+            ..Self::default()
         }
     }
 }
 
-///
-/// Cache to cache the results of `WarframeClient`. This threadsafe because it internally uses an
-/// arc mutex
-///
-#[derive(Debug, Default, Clone)]
-pub struct WarframeCache<T: std::hash::Hash + PartialEq + Eq> {
-    map: Arc<Mutex<HashMap<CacheKey<T>, CacheEntry<String>>>>,
-}
+impl<T: Transport> WarframeClient<T> {
+    ///
+    /// Get the whole data for a `model::Platform` and a choosen `Language`
+    ///
+    /// `platform` and `lang` fall back to the defaults of the client when `None`
+    ///
+    pub async fn get_platform(
+        &self,
+        platform: impl Into<Option<PlatformType>>,
+        lang: impl Into<Option<Language>>,
+    ) -> error::Result<model::Platform> {
+        self.get_section("", platform, lang).await
+    }
 
-impl<T: std::hash::Hash + PartialEq + Eq> WarframeCache<T> {
-    pub fn new() -> Self {
-        Self {
-            map: Arc::new(Mutex::new(HashMap::new())),
+    ///
+    /// Get the worldstates of every platform concurrently, at most `concurrency` at a time (see
+    /// `WarframeClientBuilder::concurrency`)
+    ///
+    /// A platform that couldn't be fetched doesn't affect the others, its error is returned
+    /// instead
+    ///
+    pub async fn get_all_platforms(
+        &self,
+        lang: impl Into<Option<Language>>,
+    ) -> HashMap<PlatformType, error::Result<model::Platform>> {
+        let lang = lang.into();
+        stream::iter(PlatformType::all())
+            .map(|platform| async move { (platform, self.get_platform(platform, lang).await) })
+            .buffer_unordered(self.concurrency.max(1))
+            .collect()
+            .await
+    }
+
+    for_each_section!(section_getters);
+
+    for_each_localized_section!(localized_getters);
+
+    ///
+    /// Url of a worldstate section, `path` is empty for the whole worldstate
+    ///
+    fn section_url(&self, path: &str, platform: PlatformType, lang: Language) -> Url {
+        let mut url = self.base_url.clone();
+        let platform: &str = platform.into();
+        if path.is_empty() {
+            url.set_path(platform);
+        } else {
+            url.set_path(&format!("{}/{}", platform, path));
+        }
+        url.set_query(Some(&format!("language={}", lang)));
+        url
+    }
+
+    ///
+    /// Send a GET request to `url`, retrying transient errors according to the retry policy
+    ///
+    /// Nothing is sent in snapshot mode
+    ///
+    async fn request(
+        &self,
+        url: &Url,
+        cached: Option<&CacheEntry<String>>,
+    ) -> error::Result<TransportResponse> {
+        if self.snapshot.is_some() {
+            return Err(WarframeError::NotInSnapshot {
+                url: url.to_string(),
+            });
+        }
+        let mut attempt = 1;
+        loop {
+            let err = match self.send(url, cached).await {
+                Ok(res) => return Ok(res),
+                Err(err) => err,
+            };
+            let Some(delay) = self.retry_policy.delay_for(attempt, &err) else {
+                return Err(err);
+            };
+            self.retry_policy.notify(&RetryEvent {
+                attempt,
+                delay,
+                error: &err,
+            });
+            tokio::time::sleep(delay).await;
+            attempt += 1;
         }
     }
 
     ///
-    /// get a json from the cache, returns `None` if not cached
+    /// Send a single GET request, mapping unsuccessful status codes to errors
     ///
-    pub async fn get(&self, key: T, language: Language, platform: PlatformType) -> Option<String> {
-        let locked_map = self.map.lock().await;
-        let item = locked_map.get(&CacheKey::new(key, language, platform));
-        if let Some(i) = item {
-            if i.is_expired() {
-                return None;
+    /// If a `cached` entry is given, the request is made conditional on its validators and may
+    /// result in `304 Not Modified`
+    ///
+    async fn send(
+        &self,
+        url: &Url,
+        cached: Option<&CacheEntry<String>>,
+    ) -> error::Result<TransportResponse> {
+        let mut headers = HeaderMap::new();
+        let validators = [
+            (IF_NONE_MATCH, cached.and_then(|entry| entry.etag.as_ref())),
+            (
+                IF_MODIFIED_SINCE,
+                cached.and_then(|entry| entry.last_modified.as_ref()),
+            ),
+        ];
+        for (name, value) in validators {
+            if let Some(value) = value.and_then(|value| value.parse().ok()) {
+                headers.insert(name, value);
             }
         }
-        item.map(|entry| entry.entry.to_string())
+        let res = self.transport.get(url, headers).await?;
+        match res.status {
+            status if status.is_success() => Ok(res),
+            StatusCode::NOT_MODIFIED if cached.is_some() => Ok(res),
+            StatusCode::NOT_FOUND => Err(WarframeError::NotFound {
+                url: url.to_string(),
+            }),
+            StatusCode::TOO_MANY_REQUESTS => Err(WarframeError::RateLimited {
+                retry_after: res.headers.get(RETRY_AFTER).and_then(parse_retry_after),
+            }),
+            status => Err(WarframeError::Status {
+                status,
+                body: res.body,
+            }),
+        }
     }
 
-    pub async fn insert(
-        &mut self,
-        key: T,
-        language: Language,
-        platform: PlatformType,
-        entry: String,
-        expiration_time: DateTime<Local>,
-    ) {
-        let mut map = self.map.lock().await;
-        map.insert(
-            CacheKey::new(key, language, platform),
-            CacheEntry::new(entry, expiration_time),
-        );
+    ///
+    /// Get a section of the worldstate in every language of `langs` and merge them, the first
+    /// language is the base of the merged section
+    ///
+    async fn get_localized_section<S>(
+        &self,
+        path: &'static str,
+        platform: impl Into<Option<PlatformType>>,
+        langs: &[Language],
+    ) -> error::Result<S::Localized>
+    where
+        S: DeserializeOwned + EarliestExpiry + Localize,
+    {
+        let platform = platform.into();
+        let langs = match langs {
+            [] => vec![self.language],
+            langs => langs.to_vec(),
+        };
+        let sections: Vec<_> = stream::iter(langs)
+            .map(|lang| async move { (lang, self.get_section::<S>(path, platform, lang).await) })
+            .buffered(self.concurrency.max(1))
+            .collect()
+            .await;
+        let mut localized = None;
+        for (lang, section) in sections {
+            match localized.as_mut() {
+                None => localized = Some(section?.localize(lang)),
+                Some(localized) => S::merge(localized, section?, lang),
+            }
+        }
+        Ok(localized.expect("at least one language is fetched"))
     }
-}
 
-impl WarframeClient {
     ///
-    /// Create new WarframeClient that can be reused for multiple requests
+    /// Get a section of the worldstate, served from the cache if it wasn't expired yet
     ///
-    pub fn new() -> Self {
-        Self::default()
+    async fn get_section<S: DeserializeOwned + EarliestExpiry>(
+        &self,
+        path: &'static str,
+        platform: impl Into<Option<PlatformType>>,
+        lang: impl Into<Option<Language>>,
+    ) -> error::Result<S> {
+        let platform = platform.into().unwrap_or(self.platform);
+        let lang = lang.into().unwrap_or(self.language);
+        if let Some(snapshot) = &self.snapshot {
+            return snapshot.section(path, platform, lang).await;
+        }
+        let url = self.section_url(path, platform, lang);
+        self.get_cached(path.to_string(), &url, platform, lang, |s: &S| {
+            self.cache_policy.expiration_time(s.earliest_expiry())
+        })
+        .await
     }
 
     ///
-    /// Get the whole data for a `model::Platform` and a choosen `Language`
+    /// Get platform independent data at the path `segments`, cached for
+    /// `CachePolicy::static_ttl`
     ///
-    pub async fn get_platform(
+    async fn get_static<S: DeserializeOwned>(
         &self,
-        platform: PlatformType,
-        lang: Language,
-    ) -> Result<model::Platform, Box<dyn std::error::Error>> {
-        let mut get_url = self.base_url.clone();
-        get_url.set_path(platform.into());
-        get_url.set_query(Some(&format!("language={}", lang.to_string())));
-        let res = self.http.get(get_url).send().await?;
-        let platform: model::Platform = res.json().await?;
-        Ok(platform)
+        segments: &[&str],
+        query: &[(&str, &str)],
+        lang: impl Into<Option<Language>>,
+    ) -> error::Result<S> {
+        let lang = lang.into().unwrap_or(self.language);
+        let url = self.url(segments, query, lang);
+        let key = format!("{}?{}", url.path(), url.query().unwrap_or_default());
+        self.get_cached(key, &url, PlatformType::default(), lang, |_: &S| {
+            self.cache_policy.static_expiration_time()
+        })
+        .await
     }
 
-    async fn request(
+    ///
+    /// Url of the path `segments` with the `language` and `query` parameters
+    ///
+    fn url(&self, segments: &[&str], query: &[(&str, &str)], lang: Language) -> Url {
+        let mut url = self.base_url.clone();
+        if let Ok(mut path) = url.path_segments_mut() {
+            path.clear().extend(segments);
+        }
+        url.query_pairs_mut()
+            .append_pair("language", lang.into())
+            .extend_pairs(query);
+        url
+    }
+
+    ///
+    /// Get `url`, served from the cache under `key` if it wasn't expired yet
+    ///
+    /// `expiration` tells when a fetched response should be evicted, `None` doesn't cache it
+    ///
+    async fn get_cached<S, E>(
         &self,
-        path: &str,
+        key: String,
+        url: &Url,
         platform: PlatformType,
         lang: Language,
-    ) -> Result<Response, reqwest::Error> {
-        let mut get_url = self.base_url.clone();
-        get_url.set_path((platform.to_string() + path).as_str());
-        get_url.set_query(Some(&format!("language={}", lang.to_string())));
-        self.http.get(get_url).send().await
+        expiration: E,
+    ) -> error::Result<S>
+    where
+        S: DeserializeOwned,
+        E: Fn(&S) -> Option<DateTime<Local>>,
+    {
+        if !self.cache_policy.enabled {
+            let res = self.request(url, None).await?;
+            return from_json(&res.body);
+        }
+        // an unreadable cache is treated as a miss, the response is fetched instead
+        if let Ok(Some(s)) = self.cache.get(key.clone(), lang, platform).await {
+            return from_json(&s);
+        }
+        let text = self
+            .cache
+            .single_flight(key.clone(), lang, platform, || {
+                self.fetch(key, url, platform, lang, expiration)
+            })
+            .await?;
+        from_json(&text)
     }
 
-    pub async fn get_alerts(
-        &mut self,
+    ///
+    /// Fetch `url` and put it into the cache, revalidating an expired entry if possible
+    ///
+    /// Returns the json of the response. Failing to read or write the cache doesn't fail the
+    /// request, the entry is fetched again next time
+    ///
+    async fn fetch<S, E>(
+        &self,
+        key: String,
+        url: &Url,
         platform: PlatformType,
         lang: Language,
-    ) -> Result<Vec<Alert>, Box<dyn std::error::Error>> {
-        if let Some(s) = self.cache.get("alerts", lang, platform).await {
-            return serde_json::from_str(&s)?;
+        expiration: E,
+    ) -> error::Result<String>
+    where
+        S: DeserializeOwned,
+        E: Fn(&S) -> Option<DateTime<Local>>,
+    {
+        let cached = self
+            .cache
+            .get_entry(key.clone(), lang, platform)
+            .await
+            .unwrap_or(None);
+        if let Some(entry) = cached.as_ref().filter(|entry| !entry.is_expired()) {
+            return Ok(entry.entry.clone());
         }
-        let res = self.request("/alerts", platform, lang).await?;
-        let obj: Vec<Alert> = res.json().await?;
+        let cached = cached.filter(CacheEntry::has_validators);
+        let res = self.request(url, cached.as_ref()).await?;
+        if let (StatusCode::NOT_MODIFIED, Some(entry)) = (res.status, cached) {
+            let obj: S = from_json(&entry.entry)?;
+            if let Some(expiration_time) = expiration(&obj) {
+                let _ = self
+                    .cache
+                    .refresh(key, lang, platform, expiration_time)
+                    .await;
+            }
+            return Ok(entry.entry);
+        }
+        let etag = header_string(&res, ETAG);
+        let last_modified = header_string(&res, LAST_MODIFIED);
+        let text = res.body;
+        let obj: S = from_json(&text)?;
+        if let Some(expiration_time) = expiration(&obj) {
+            let entry =
+                CacheEntry::new(text.clone(), expiration_time).with_validators(etag, last_modified);
+            let _ = self.cache.insert_entry(key, lang, platform, entry).await;
+        }
+        Ok(text)
     }
 }
 
+fn header_string(res: &TransportResponse, name: HeaderName) -> Option<String> {
+    res.headers
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string)
+}
+
 #[cfg(test)]
 mod tests {
     use crate::model::*;
-    use crate::WarframeClient;
+    use crate::transport::{FixtureTransport, TransportResponse};
+    use crate::{
+        CacheEntry, CachePolicy, DiskBackend, RetryPolicy, Snapshot, Transport, TransportErrorKind,
+        WarframeCache, WarframeClient, WarframeError,
+    };
+    use chrono::{Duration, Local};
+    use reqwest::StatusCode;
     use std::fs;
     use std::path::PathBuf;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
 
     #[test]
     pub fn test_platform_deserialize() -> Result<(), Box<dyn std::error::Error>> {
@@ -188,6 +552,48 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    pub fn test_typed_keys() -> Result<(), Box<dyn std::error::Error>> {
+        let mut test_json = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_json.push("resources/test/pc_test.json");
+        let content = fs::read_to_string(test_json)?;
+        let mut platform: Platform = serde_json::from_str(&content)?;
+        let fissure = &platform.fissures[0];
+        assert_eq!(fissure.tier_kind(), FissureTier::Neo);
+        assert_eq!(fissure.enemy_key, Faction::Corpus);
+        assert_eq!(fissure.mission_key, MissionType::Defense);
+        assert_eq!(platform.arbitration.type_key, MissionType::Survival);
+        assert_eq!(platform.archon_hunt.faction_key, Faction::Narmer);
+        assert_eq!(platform.cetus_cycle.state_kind(), CycleState::Day);
+        assert_eq!(platform.duviri_cycle.state_kind(), CycleState::Fear);
+        assert_eq!(
+            MissionType::from("mobile defense"),
+            MissionType::MobileDefense
+        );
+        let flood = Kuva {
+            kuva_type: "Kuva Flood".to_string(),
+            ..Kuva::default()
+        };
+        assert_eq!(flood.kuva_kind(), KuvaMission::Flood);
+        assert_eq!(KuvaMission::from("siphon"), KuvaMission::Siphon);
+        assert_eq!(
+            KuvaMission::from("Kuva-Flut"),
+            KuvaMission::Unknown("Kuva-Flut".to_string())
+        );
+
+        // localized display strings don't affect the typed keys
+        platform.fissures[0].mission_type = "Verteidigung".to_string();
+        platform.fissures[0].enemy_key = Faction::from("Zariman Angels");
+        let json = serde_json::to_string(&platform)?;
+        let platform: Platform = serde_json::from_str(&json)?;
+        assert_eq!(platform.fissures[0].mission_key, MissionType::Defense);
+        assert_eq!(
+            platform.fissures[0].enemy_key,
+            Faction::Unknown("Zariman Angels".to_string())
+        );
+        Ok(())
+    }
+
     #[test]
     pub fn test_platform_lang() -> Result<(), Box<dyn std::error::Error>> {
         let mut test_json = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
@@ -201,8 +607,479 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    pub fn test_deserialize_error_path() {
+        let err = crate::error::from_json::<Sortie>(r#"{"id": 5}"#).unwrap_err();
+        match err {
+            WarframeError::Deserialize { path, .. } => assert_eq!(path, "id"),
+            other => panic!("expected a deserialize error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    pub fn test_error_is_send_sync() {
+        fn assert_send_sync<T: Send + Sync + 'static>() {}
+        assert_send_sync::<WarframeError>();
+    }
+
+    #[test]
+    pub fn test_earliest_expiry() -> Result<(), Box<dyn std::error::Error>> {
+        let mut test_json = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_json.push("resources/test/pc_test.json");
+        let content = fs::read_to_string(test_json)?;
+        let mut pl: Platform = serde_json::from_str(&content)?;
+        // most of the test worldstate expired long ago, e.g. the arbitration
+        assert!(pl.arbitration.expired);
+        assert!(pl
+            .earliest_expiry()
+            .is_none_or(|expiry| expiry > Local::now()));
+        let ttl = CachePolicy::default().expiration_time(pl.arbitration.earliest_expiry());
+        assert!(ttl.unwrap() - Local::now() > Duration::seconds(59));
+
+        let now = Local::now();
+        pl.fissures[1].expiry = now + Duration::minutes(30);
+        pl.fissures[2].expiry = now + Duration::minutes(20);
+        let fissures = pl.fissures.earliest_expiry().unwrap();
+        assert_eq!(fissures, now + Duration::minutes(20));
+        assert_eq!(pl.earliest_expiry(), Some(fissures));
+        assert_eq!(pl.news.earliest_expiry(), None);
+        assert_eq!(
+            pl.void_trader.earliest_expiry(),
+            Some(pl.void_trader.activation)
+        );
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_cache_policy_clamps_ttl() {
+        let policy = CachePolicy::default();
+        let now = Local::now();
+        let ttl = |expiry| policy.expiration_time(expiry).unwrap() - now;
+        assert!(ttl(Some(now - Duration::hours(1))) >= Duration::seconds(10));
+        assert!(ttl(Some(now + Duration::days(4))) <= Duration::seconds(5 * 60 + 1));
+        let in_two_minutes = ttl(Some(now + Duration::minutes(2)));
+        assert!(
+            in_two_minutes > Duration::seconds(119) && in_two_minutes <= Duration::seconds(121)
+        );
+        let no_expiry = ttl(None);
+        assert!(no_expiry >= Duration::seconds(60) && no_expiry <= Duration::seconds(61));
+    }
+
+    #[tokio::test]
+    pub async fn test_disk_backend_shared() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = tempfile::tempdir()?;
+        let shard_one: WarframeCache<&str> =
+            WarframeCache::with_backend(Arc::new(DiskBackend::new(dir.path())));
+        let shard_two: WarframeCache<&str> =
+            WarframeCache::with_backend(Arc::new(DiskBackend::new(dir.path())));
+        let entry = CacheEntry::new("{}".to_string(), Local::now() + Duration::seconds(60))
+            .with_validators(None, Some("Mon, 18 Mar 2024 02:13:16 GMT".to_string()));
+        shard_one
+            .insert_entry("", Language::English, PlatformType::PC, entry)
+            .await?;
+        let shared = shard_two
+            .get_entry("", Language::English, PlatformType::PC)
+            .await?
+            .unwrap();
+        assert_eq!(shared.entry, "{}");
+        assert!(!shared.is_expired());
+        assert!(shared.last_modified.is_some());
+        assert!(shard_two
+            .get("", Language::German, PlatformType::PC)
+            .await?
+            .is_none());
+
+        let profile = CacheEntry::new("{}".to_string(), Local::now() + Duration::seconds(60));
+        shard_one
+            .insert_entry(
+                "profile/tenno.one",
+                Language::English,
+                PlatformType::PC,
+                profile,
+            )
+            .await?;
+        for key in [
+            "profile/tenno_one",
+            "profile/Tenno.one",
+            "profile/tenno-one",
+        ] {
+            assert!(shard_two
+                .get(key, Language::English, PlatformType::PC)
+                .await?
+                .is_none());
+        }
+
+        let writes = (0..16).map(|_| {
+            let entry = CacheEntry::new("{}".to_string(), Local::now() + Duration::seconds(60));
+            shard_one.insert_entry("alerts", Language::English, PlatformType::PC, entry)
+        });
+        for write in futures::future::join_all(writes).await {
+            write?;
+        }
+
+        let long_key = format!("weapons/search/{}", "PRIME".repeat(60));
+        let entry = CacheEntry::new("[]".to_string(), Local::now() + Duration::seconds(60));
+        shard_one
+            .insert_entry(&long_key, Language::English, PlatformType::PC, entry)
+            .await?;
+        let cached = shard_two
+            .get(&long_key, Language::English, PlatformType::PC)
+            .await?;
+        assert_eq!(cached.as_deref(), Some("[]"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    pub async fn test_unreadable_cache_is_a_miss() -> Result<(), Box<dyn std::error::Error>> {
+        let file = tempfile::NamedTempFile::new()?;
+        let fixtures = FixtureTransport::new().with_json("/pc/alerts", "[]");
+        // a file instead of a directory, every read and write of the cache fails
+        let client = WarframeClient::builder()
+            .cache_backend(DiskBackend::new(file.path()))
+            .build_with_transport(fixtures.clone())?;
+        assert!(client.get_alerts(None, None).await?.is_empty());
+        assert!(client.get_alerts(None, None).await?.is_empty());
+        assert_eq!(fixtures.requests().len(), 2);
+        Ok(())
+    }
+
+    #[tokio::test]
+    pub async fn test_snapshot_client() -> Result<(), Box<dyn std::error::Error>> {
+        let mut test_json = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_json.push("resources/test/pc_test.json");
+        let dir = tempfile::tempdir()?;
+        fs::copy(&test_json, dir.path().join("pc.json"))?;
+        let client = WarframeClient::from_snapshot(dir.path());
+        let pl = client.get_platform(None, None).await?;
+        assert_eq!(client.get_fissures(None, None).await?, pl.fissures);
+        assert_eq!(
+            client.get_cetus_cycle(None, Language::German).await?,
+            pl.cetus_cycle
+        );
+        assert!(matches!(
+            client.get_alerts(PlatformType::PS4, None).await,
+            Err(WarframeError::NotFound { .. })
+        ));
+        let mut changed = pl.clone();
+        changed.fissures.clear();
+        Snapshot::new(dir.path())
+            .save(PlatformType::PC, Language::German, &changed)
+            .await?;
+        assert!(client
+            .get_fissures(None, Language::German)
+            .await?
+            .is_empty());
+        assert_eq!(client.get_fissures(None, None).await?, pl.fissures);
+        assert!(matches!(
+            client.get_rivens(None).await,
+            Err(WarframeError::NotInSnapshot { .. })
+        ));
+        Ok(())
+    }
+
+    #[tokio::test]
+    pub async fn test_fixture_transport_caching() -> Result<(), Box<dyn std::error::Error>> {
+        let fixtures = FixtureTransport::new().with_json("/pc/alerts", "[]");
+        let client = WarframeClient::builder().build_with_transport(fixtures.clone())?;
+        client.get_alerts(None, None).await?;
+        client.get_alerts(None, None).await?;
+        client.get_alerts(None, Language::German).await?;
+        let requests = fixtures.requests();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[1].0.query(), Some("language=de"));
+        assert!(matches!(
+            client.get_fissures(None, None).await,
+            Err(WarframeError::NotFound { .. })
+        ));
+        Ok(())
+    }
+
+    #[tokio::test]
+    pub async fn test_fixture_transport_revalidation() -> Result<(), Box<dyn std::error::Error>> {
+        let fixtures = FixtureTransport::new()
+            .with_response(
+                "/pc/news",
+                TransportResponse::ok("[]").with_header("etag", "\"v1\""),
+            )
+            .with_response(
+                "/pc/news",
+                TransportResponse::new(StatusCode::NOT_MODIFIED, ""),
+            );
+        let policy = CachePolicy {
+            ttl: std::time::Duration::ZERO,
+            ..CachePolicy::default()
+        };
+        let client = WarframeClient::builder()
+            .cache_policy(policy)
+            .build_with_transport(fixtures.clone())?;
+        assert!(client.get_news(None, None).await?.is_empty());
+        assert!(client.get_news(None, None).await?.is_empty());
+        let requests = fixtures.requests();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[1].1.get("if-none-match").unwrap(), "\"v1\"");
+        Ok(())
+    }
+
+    ///
+    /// Fails the first `failures` requests with `kind`, then answers like `fixtures`
+    ///
+    #[derive(Debug)]
+    struct FlakyTransport {
+        kind: TransportErrorKind,
+        failures: AtomicUsize,
+        fixtures: FixtureTransport,
+    }
+
+    #[async_trait::async_trait]
+    impl Transport for FlakyTransport {
+        async fn get(
+            &self,
+            url: &url::Url,
+            headers: reqwest::header::HeaderMap,
+        ) -> crate::error::Result<TransportResponse> {
+            let failed = self
+                .failures
+                .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1));
+            if failed.is_ok() {
+                return Err(WarframeError::custom_transport(
+                    self.kind,
+                    "connection refused by the stand-in",
+                ));
+            }
+            self.fixtures.get(url, headers).await
+        }
+    }
+
+    #[tokio::test]
+    pub async fn test_custom_transport_errors() -> Result<(), Box<dyn std::error::Error>> {
+        let mut policy = RetryPolicy::default();
+        policy.base_delay = std::time::Duration::ZERO;
+        let flaky = |kind| FlakyTransport {
+            kind,
+            failures: AtomicUsize::new(1),
+            fixtures: FixtureTransport::new().with_json("/pc/alerts", "[]"),
+        };
+        let client = WarframeClient::builder()
+            .retry_policy(policy.clone())
+            .build_with_transport(flaky(TransportErrorKind::Connect))?;
+        assert!(client.get_alerts(None, None).await?.is_empty());
+        let client = WarframeClient::builder()
+            .retry_policy(policy)
+            .build_with_transport(flaky(TransportErrorKind::Other))?;
+        assert!(matches!(
+            client.get_alerts(None, None).await,
+            Err(WarframeError::CustomTransport {
+                kind: TransportErrorKind::Other,
+                ..
+            })
+        ));
+        Ok(())
+    }
+
+    #[tokio::test]
+    pub async fn test_fixture_transport_retry() -> Result<(), Box<dyn std::error::Error>> {
+        let fixtures = FixtureTransport::new()
+            .with_response(
+                "/pc/simaris",
+                TransportResponse::new(StatusCode::SERVICE_UNAVAILABLE, "down"),
+            )
+            .with_json(
+                "/pc/simaris",
+                r#"{"target":"Guardsman","isTargetActive":false,"asString":""}"#,
+            );
+        let retries = Arc::new(AtomicUsize::new(0));
+        let mut policy = RetryPolicy::default().on_retry({
+            let retries = retries.clone();
+            move |_| {
+                retries.fetch_add(1, Ordering::SeqCst);
+            }
+        });
+        policy.base_delay = std::time::Duration::ZERO;
+        let client = WarframeClient::builder()
+            .retry_policy(policy)
+            .build_with_transport(fixtures)?;
+        assert_eq!(client.get_simaris(None, None).await?.target, "Guardsman");
+        assert_eq!(retries.load(Ordering::SeqCst), 1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    pub async fn test_get_all_platforms() -> Result<(), Box<dyn std::error::Error>> {
+        let mut test_json = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_json.push("resources/test/pc_test.json");
+        let content = fs::read_to_string(test_json)?;
+        let fixtures = FixtureTransport::new()
+            .with_json("/pc", content.clone())
+            .with_json("/ps4", content.clone())
+            .with_json("/swi", content);
+        let client = WarframeClient::builder()
+            .concurrency(2)
+            .build_with_transport(fixtures.clone())?;
+        let platforms = client.get_all_platforms(Language::English).await;
+        assert_eq!(platforms.len(), PlatformType::all().count());
+        assert!(platforms[&PlatformType::PC].is_ok());
+        assert!(platforms[&PlatformType::Switch].is_ok());
+        assert!(matches!(
+            platforms[&PlatformType::XBox],
+            Err(WarframeError::NotFound { .. })
+        ));
+        assert_eq!(fixtures.requests().len(), 4);
+        Ok(())
+    }
+
     #[test]
     pub fn test_base_url_parsing() {
         WarframeClient::new();
     }
+
+    #[tokio::test]
+    pub async fn test_cache_revalidation() -> Result<(), Box<dyn std::error::Error>> {
+        let cache = WarframeCache::new();
+        let stale = CacheEntry::new("[]".to_string(), Local::now() - Duration::seconds(1))
+            .with_validators(Some("\"abc\"".to_string()), None);
+        cache
+            .insert_entry("alerts", Language::English, PlatformType::PC, stale)
+            .await?;
+        assert!(cache
+            .get("alerts", Language::English, PlatformType::PC)
+            .await?
+            .is_none());
+        let entry = cache
+            .get_entry("alerts", Language::English, PlatformType::PC)
+            .await?
+            .unwrap();
+        assert_eq!(entry.etag.as_deref(), Some("\"abc\""));
+        assert!(
+            cache
+                .refresh(
+                    "alerts",
+                    Language::English,
+                    PlatformType::PC,
+                    Local::now() + Duration::seconds(60)
+                )
+                .await?
+        );
+        assert_eq!(
+            cache
+                .get("alerts", Language::English, PlatformType::PC)
+                .await?
+                .as_deref(),
+            Some("[]")
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    pub async fn test_single_flight() {
+        let cache = WarframeCache::new();
+        let fetches = Arc::new(AtomicUsize::new(0));
+        let calls = (0..10).map(|_| {
+            let cache = cache.clone();
+            let fetches = fetches.clone();
+            tokio::spawn(async move {
+                cache
+                    .single_flight("alerts", Language::English, PlatformType::PC, || async {
+                        fetches.fetch_add(1, Ordering::SeqCst);
+                        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+                        Err(WarframeError::NotFound {
+                            url: "alerts".to_string(),
+                        })
+                    })
+                    .await
+            })
+        });
+        for res in futures::future::join_all(calls).await {
+            assert!(matches!(res.unwrap(), Err(WarframeError::NotFound { .. })));
+        }
+        assert_eq!(fetches.load(Ordering::SeqCst), 1);
+        let res = cache
+            .single_flight("alerts", Language::English, PlatformType::PC, || async {
+                Ok("[]".to_string())
+            })
+            .await;
+        assert_eq!(res.unwrap(), "[]");
+    }
+
+    #[test]
+    pub fn test_retry_backoff() {
+        let mut policy = RetryPolicy::default();
+        policy.max_attempts = 4;
+        policy.base_delay = std::time::Duration::from_millis(100);
+        policy.max_delay = std::time::Duration::from_millis(300);
+        policy.jitter = false;
+        let unavailable = WarframeError::Status {
+            status: reqwest::StatusCode::SERVICE_UNAVAILABLE,
+            body: String::new(),
+        };
+        let delays: Vec<_> = (1..=4)
+            .map(|attempt| policy.delay_for(attempt, &unavailable))
+            .collect();
+        assert_eq!(
+            delays,
+            vec![
+                Some(std::time::Duration::from_millis(100)),
+                Some(std::time::Duration::from_millis(200)),
+                Some(std::time::Duration::from_millis(300)),
+                None,
+            ]
+        );
+        let rate_limited = WarframeError::RateLimited {
+            retry_after: Some(std::time::Duration::from_millis(250)),
+        };
+        assert_eq!(
+            policy.delay_for(1, &rate_limited),
+            Some(std::time::Duration::from_millis(250))
+        );
+        let rate_limited_for_a_day = WarframeError::RateLimited {
+            retry_after: Some(std::time::Duration::from_secs(86400)),
+        };
+        assert_eq!(policy.delay_for(1, &rate_limited_for_a_day), None);
+        let not_found = WarframeError::NotFound { url: String::new() };
+        assert_eq!(policy.delay_for(1, &not_found), None);
+    }
+
+    #[test]
+    pub fn test_builder_invalid_base_url() {
+        let res = WarframeClient::builder().base_url("not a url").build();
+        assert!(matches!(res, Err(WarframeError::InvalidUrl(_))));
+    }
+
+    #[tokio::test]
+    pub async fn test_cache_expiry() -> Result<(), Box<dyn std::error::Error>> {
+        let cache = WarframeCache::new();
+        cache
+            .insert(
+                "alerts",
+                Language::English,
+                PlatformType::PC,
+                "[]".to_string(),
+                Local::now() + Duration::seconds(60),
+            )
+            .await?;
+        cache
+            .insert(
+                "fissures",
+                Language::English,
+                PlatformType::PC,
+                "[]".to_string(),
+                Local::now() - Duration::seconds(1),
+            )
+            .await?;
+        assert_eq!(
+            cache
+                .get("alerts", Language::English, PlatformType::PC)
+                .await?
+                .as_deref(),
+            Some("[]")
+        );
+        assert!(cache
+            .get("alerts", Language::German, PlatformType::PC)
+            .await?
+            .is_none());
+        assert!(cache
+            .get("fissures", Language::English, PlatformType::PC)
+            .await?
+            .is_none());
+        Ok(())
+    }
 }
@@ -0,0 +1,410 @@
+//!
+//! Sections of the worldstate fetched in multiple languages at once, with their descriptive
+//! fields merged into per-language maps
+//!
+use crate::model::*;
+use std::collections::HashMap;
+
+///
+/// A descriptive string of the worldstate in every fetched language
+///
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LocalizedText(HashMap<Language, String>);
+
+impl LocalizedText {
+    fn new(lang: Language, text: &str) -> Self {
+        let mut localized = Self::default();
+        localized.insert(lang, text);
+        localized
+    }
+
+    fn insert(&mut self, lang: Language, text: &str) {
+        self.0.insert(lang, text.to_string());
+    }
+
+    pub fn get(&self, lang: Language) -> Option<&str> {
+        self.0.get(&lang).map(String::as_str)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Language, &str)> {
+        self.0.iter().map(|(lang, text)| (*lang, text.as_str()))
+    }
+}
+
+///
+/// Language independent key to match the same entry across languages, e.g. the `id`
+///
+pub trait StableKey {
+    fn stable_key(&self) -> &str;
+}
+
+///
+/// A section of the worldstate that can be merged across languages
+///
+pub trait Localize: Sized {
+    type Localized;
+
+    ///
+    /// Start a localized value from `self`, fetched in `lang`
+    ///
+    fn localize(self, lang: Language) -> Self::Localized;
+
+    ///
+    /// Add the descriptive fields of `other`, fetched in `lang`, to `localized`
+    ///
+    fn merge(localized: &mut Self::Localized, other: Self, lang: Language);
+}
+
+///
+/// Entries are matched by their `StableKey`, entries only present in some languages are kept
+///
+impl<T> Localize for Vec<T>
+where
+    T: Localize + StableKey,
+    T::Localized: StableKey,
+{
+    type Localized = Vec<T::Localized>;
+
+    fn localize(self, lang: Language) -> Self::Localized {
+        self.into_iter().map(|item| item.localize(lang)).collect()
+    }
+
+    fn merge(localized: &mut Self::Localized, other: Self, lang: Language) {
+        for item in other {
+            let existing = localized
+                .iter_mut()
+                .find(|existing| existing.stable_key() == item.stable_key());
+            match existing {
+                Some(existing) => T::merge(existing, item, lang),
+                None => localized.push(item.localize(lang)),
+            }
+        }
+    }
+}
+
+///
+/// A `Fissure` with its descriptive fields in every fetched language
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct LocalizedFissure {
+    /// The fissure as received in the first language
+    pub fissure: Fissure,
+    pub node: LocalizedText,
+    pub mission_type: LocalizedText,
+    pub enemy: LocalizedText,
+    pub tier: LocalizedText,
+}
+
+impl StableKey for Fissure {
+    fn stable_key(&self) -> &str {
+        &self.id
+    }
+}
+
+impl StableKey for LocalizedFissure {
+    fn stable_key(&self) -> &str {
+        &self.fissure.id
+    }
+}
+
+impl Localize for Fissure {
+    type Localized = LocalizedFissure;
+
+    fn localize(self, lang: Language) -> LocalizedFissure {
+        LocalizedFissure {
+            node: LocalizedText::new(lang, &self.node),
+            mission_type: LocalizedText::new(lang, &self.mission_type),
+            enemy: LocalizedText::new(lang, &self.enemy),
+            tier: LocalizedText::new(lang, &self.tier),
+            fissure: self,
+        }
+    }
+
+    fn merge(localized: &mut LocalizedFissure, other: Self, lang: Language) {
+        localized.node.insert(lang, &other.node);
+        localized.mission_type.insert(lang, &other.mission_type);
+        localized.enemy.insert(lang, &other.enemy);
+        localized.tier.insert(lang, &other.tier);
+    }
+}
+
+///
+/// A `Mission` with its descriptive fields in every fetched language
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct LocalizedMission {
+    pub node: LocalizedText,
+    pub mission_type: LocalizedText,
+    pub faction: LocalizedText,
+    /// `Reward::as_string`
+    pub reward: LocalizedText,
+}
+
+impl Localize for Mission {
+    type Localized = LocalizedMission;
+
+    fn localize(self, lang: Language) -> LocalizedMission {
+        LocalizedMission {
+            node: LocalizedText::new(lang, &self.node),
+            mission_type: LocalizedText::new(lang, &self.type_field),
+            faction: LocalizedText::new(lang, &self.faction),
+            reward: LocalizedText::new(lang, &self.reward.as_string),
+        }
+    }
+
+    fn merge(localized: &mut LocalizedMission, other: Self, lang: Language) {
+        localized.node.insert(lang, &other.node);
+        localized.mission_type.insert(lang, &other.type_field);
+        localized.faction.insert(lang, &other.faction);
+        localized.reward.insert(lang, &other.reward.as_string);
+    }
+}
+
+///
+/// An `Alert` with the descriptive fields of its mission in every fetched language
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct LocalizedAlert {
+    /// The alert as received in the first language
+    pub alert: Alert,
+    pub mission: LocalizedMission,
+}
+
+impl StableKey for Alert {
+    fn stable_key(&self) -> &str {
+        &self.id
+    }
+}
+
+impl StableKey for LocalizedAlert {
+    fn stable_key(&self) -> &str {
+        &self.alert.id
+    }
+}
+
+impl Localize for Alert {
+    type Localized = LocalizedAlert;
+
+    fn localize(self, lang: Language) -> LocalizedAlert {
+        LocalizedAlert {
+            mission: self.mission.clone().localize(lang),
+            alert: self,
+        }
+    }
+
+    fn merge(localized: &mut LocalizedAlert, other: Self, lang: Language) {
+        Mission::merge(&mut localized.mission, other.mission, lang);
+    }
+}
+
+///
+/// An `Invasion` with its descriptive fields in every fetched language
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct LocalizedInvasion {
+    /// The invasion as received in the first language
+    pub invasion: Invasion,
+    pub node: LocalizedText,
+    pub desc: LocalizedText,
+    /// `Reward::as_string` of the attacker, empty if the attacker offers no reward
+    pub attacker_reward: LocalizedText,
+    /// `Reward::as_string` of the defender
+    pub defender_reward: LocalizedText,
+}
+
+impl StableKey for Invasion {
+    fn stable_key(&self) -> &str {
+        &self.id
+    }
+}
+
+impl StableKey for LocalizedInvasion {
+    fn stable_key(&self) -> &str {
+        &self.invasion.id
+    }
+}
+
+fn attacker_reward(invasion: &Invasion) -> &str {
+    invasion
+        .attacker
+        .reward
+        .as_ref()
+        .map_or("", |reward| reward.as_string.as_str())
+}
+
+impl Localize for Invasion {
+    type Localized = LocalizedInvasion;
+
+    fn localize(self, lang: Language) -> LocalizedInvasion {
+        LocalizedInvasion {
+            node: LocalizedText::new(lang, &self.node),
+            desc: LocalizedText::new(lang, &self.desc),
+            attacker_reward: LocalizedText::new(lang, attacker_reward(&self)),
+            defender_reward: LocalizedText::new(lang, &self.defender.reward.as_string),
+            invasion: self,
+        }
+    }
+
+    fn merge(localized: &mut LocalizedInvasion, other: Self, lang: Language) {
+        localized.node.insert(lang, &other.node);
+        localized.desc.insert(lang, &other.desc);
+        localized
+            .attacker_reward
+            .insert(lang, attacker_reward(&other));
+        localized
+            .defender_reward
+            .insert(lang, &other.defender.reward.as_string);
+    }
+}
+
+///
+/// A sortie `Variant` with its descriptive fields in every fetched language
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct LocalizedVariant {
+    /// The variant as received in the first language
+    pub variant: Variant,
+    pub node: LocalizedText,
+    pub mission_type: LocalizedText,
+    pub modifier: LocalizedText,
+    pub modifier_description: LocalizedText,
+}
+
+impl StableKey for Variant {
+    fn stable_key(&self) -> &str {
+        &self.node_key
+    }
+}
+
+impl StableKey for LocalizedVariant {
+    fn stable_key(&self) -> &str {
+        &self.variant.node_key
+    }
+}
+
+impl Localize for Variant {
+    type Localized = LocalizedVariant;
+
+    fn localize(self, lang: Language) -> LocalizedVariant {
+        LocalizedVariant {
+            node: LocalizedText::new(lang, &self.node),
+            mission_type: LocalizedText::new(lang, &self.mission_type),
+            modifier: LocalizedText::new(lang, &self.modifier),
+            modifier_description: LocalizedText::new(lang, &self.modifier_description),
+            variant: self,
+        }
+    }
+
+    fn merge(localized: &mut LocalizedVariant, other: Self, lang: Language) {
+        localized.node.insert(lang, &other.node);
+        localized.mission_type.insert(lang, &other.mission_type);
+        localized.modifier.insert(lang, &other.modifier);
+        localized
+            .modifier_description
+            .insert(lang, &other.modifier_description);
+    }
+}
+
+///
+/// A `Sortie` with the descriptive fields of its variants in every fetched language
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct LocalizedSortie {
+    /// The sortie as received in the first language
+    pub sortie: Sortie,
+    pub boss: LocalizedText,
+    pub faction: LocalizedText,
+    pub variants: Vec<LocalizedVariant>,
+}
+
+impl Localize for Sortie {
+    type Localized = LocalizedSortie;
+
+    fn localize(self, lang: Language) -> LocalizedSortie {
+        LocalizedSortie {
+            boss: LocalizedText::new(lang, &self.boss),
+            faction: LocalizedText::new(lang, &self.faction),
+            variants: self.variants.clone().localize(lang),
+            sortie: self,
+        }
+    }
+
+    fn merge(localized: &mut LocalizedSortie, other: Self, lang: Language) {
+        localized.boss.insert(lang, &other.boss);
+        localized.faction.insert(lang, &other.faction);
+        Vec::merge(&mut localized.variants, other.variants, lang);
+    }
+}
+
+///
+/// An `Arbitration` with its descriptive fields in every fetched language
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct LocalizedArbitration {
+    /// The arbitration as received in the first language
+    pub arbitration: Arbitration,
+    pub node: LocalizedText,
+    pub mission_type: LocalizedText,
+    pub enemy: LocalizedText,
+}
+
+impl Localize for Arbitration {
+    type Localized = LocalizedArbitration;
+
+    fn localize(self, lang: Language) -> LocalizedArbitration {
+        LocalizedArbitration {
+            node: LocalizedText::new(lang, &self.node),
+            mission_type: LocalizedText::new(lang, &self.type_field),
+            enemy: LocalizedText::new(lang, &self.enemy),
+            arbitration: self,
+        }
+    }
+
+    fn merge(localized: &mut LocalizedArbitration, other: Self, lang: Language) {
+        localized.node.insert(lang, &other.node);
+        localized.mission_type.insert(lang, &other.type_field);
+        localized.enemy.insert(lang, &other.enemy);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::model::*;
+    use crate::transport::FixtureTransport;
+    use crate::WarframeClient;
+    use std::fs;
+    use std::path::PathBuf;
+
+    #[tokio::test]
+    pub async fn test_localized_fissures() -> Result<(), Box<dyn std::error::Error>> {
+        let mut test_json = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_json.push("resources/test/pc_test.json");
+        let pl: Platform = serde_json::from_str(&fs::read_to_string(test_json)?)?;
+        let mut german = pl.fissures.clone();
+        german[0].mission_type = "Verteidigung".to_string();
+        german.pop();
+        let fixtures = FixtureTransport::new()
+            .with_json(
+                "/pc/fissures?language=en",
+                serde_json::to_string(&pl.fissures)?,
+            )
+            .with_json("/pc/fissures?language=de", serde_json::to_string(&german)?);
+        let client = WarframeClient::builder().build_with_transport(fixtures)?;
+        let fissures = client
+            .get_localized_fissures(None, &[Language::English, Language::German])
+            .await?;
+        assert_eq!(fissures.len(), pl.fissures.len());
+        assert_eq!(fissures[0].fissure, pl.fissures[0]);
+        assert_eq!(
+            fissures[0].mission_type.get(Language::English),
+            Some(pl.fissures[0].mission_type.as_str())
+        );
+        assert_eq!(
+            fissures[0].mission_type.get(Language::German),
+            Some("Verteidigung")
+        );
+        let last = fissures.last().unwrap();
+        assert!(last.node.get(Language::German).is_none());
+        Ok(())
+    }
+}
@@ -4,37 +4,179 @@ use serde::Deserialize;
 use serde::Serialize;
 use std::collections::HashMap;
 
+///
+/// Implemented by everything in the worldstate that is only valid until a certain time
+///
 pub trait Expirable {
     fn expiry(&self) -> &DateTime<Local>;
 }
 
+///
+/// The earliest point in time at which a response is outdated, `None` if it has no expiry
+///
+/// Used by `WarframeClient` to derive how long a response is cached. Collections only
+/// consider expiries that haven't passed yet, the api often still lists expired entries
+///
+pub trait EarliestExpiry {
+    fn earliest_expiry(&self) -> Option<DateTime<Local>>;
+}
+
+impl<T: EarliestExpiry> EarliestExpiry for Vec<T> {
+    fn earliest_expiry(&self) -> Option<DateTime<Local>> {
+        let now = Local::now();
+        self.iter()
+            .filter_map(T::earliest_expiry)
+            .filter(|expiry| *expiry > now)
+            .min()
+    }
+}
+
+macro_rules! impl_expirable {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl Expirable for $ty {
+                fn expiry(&self) -> &DateTime<Local> {
+                    &self.expiry
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_earliest_expiry {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl EarliestExpiry for $ty {
+                fn earliest_expiry(&self) -> Option<DateTime<Local>> {
+                    Some(self.expiry)
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_no_expiry {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl EarliestExpiry for $ty {
+                fn earliest_expiry(&self) -> Option<DateTime<Local>> {
+                    None
+                }
+            }
+        )*
+    };
+}
+
+///
+/// Defines an enum parsed from a language independent key with an `Unknown` fallback, keys are
+/// compared ignoring case and (de)serialized as the key string
+///
+macro_rules! key_enum {
+    (
+        $(#[$meta:meta])*
+        $name:ident {
+            $($(#[$variant_meta:meta])* $variant:ident => $key:literal $(| $alias:literal)*,)*
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+        #[serde(from = "String", into = "String")]
+        pub enum $name {
+            $($(#[$variant_meta])* $variant,)*
+            /// a key this version of the crate doesn't know yet
+            Unknown(String),
+        }
+
+        impl $name {
+            ///
+            /// The language independent key, e.g. as in the `*_key` fields
+            ///
+            pub fn key(&self) -> &str {
+                match self {
+                    $($name::$variant => $key,)*
+                    $name::Unknown(key) => key,
+                }
+            }
+        }
+
+        impl Default for $name {
+            fn default() -> Self {
+                $name::Unknown(String::new())
+            }
+        }
+
+        impl From<&str> for $name {
+            fn from(key: &str) -> Self {
+                let key = key.trim();
+                $(
+                    if key.eq_ignore_ascii_case($key) $(|| key.eq_ignore_ascii_case($alias))* {
+                        return $name::$variant;
+                    }
+                )*
+                $name::Unknown(key.to_string())
+            }
+        }
+
+        impl From<String> for $name {
+            fn from(key: String) -> Self {
+                key.as_str().into()
+            }
+        }
+
+        impl From<$name> for String {
+            fn from(value: $name) -> Self {
+                match value {
+                    $name::Unknown(key) => key,
+                    known => known.key().to_string(),
+                }
+            }
+        }
+
+        impl std::fmt::Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.write_str(self.key())
+            }
+        }
+    };
+}
+
 /// Enum to represent the different platforms
 ///
 /// Defaults to "Platform::PC"
-#[derive(PartialEq, Eq, Debug, Hash)]
+#[derive(PartialEq, Eq, Debug, Hash, Clone, Copy, Default)]
 pub enum PlatformType {
+    #[default]
     PC,
     PS4,
     XBox,
     Switch,
 }
 
-impl Default for PlatformType {
-    fn default() -> Self {
-        PlatformType::PC
+impl PlatformType {
+    ///
+    /// Every platform, e.g. to compare their worldstates
+    ///
+    pub fn all() -> impl Iterator<Item = PlatformType> {
+        [
+            PlatformType::PC,
+            PlatformType::PS4,
+            PlatformType::XBox,
+            PlatformType::Switch,
+        ]
+        .into_iter()
     }
 }
 
-impl std::string::ToString for PlatformType {
-    fn to_string(&self) -> String {
+impl std::fmt::Display for PlatformType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let into: &str = self.into();
-        into.to_string()
+        f.write_str(into)
     }
 }
 
-impl Into<&str> for &PlatformType {
-    fn into(self) -> &'static str {
-        match self {
+impl From<&PlatformType> for &'static str {
+    fn from(platform: &PlatformType) -> Self {
+        match platform {
             PlatformType::PC => "pc",
             PlatformType::PS4 => "ps4",
             PlatformType::XBox => "xb1",
@@ -43,17 +185,18 @@ impl Into<&str> for &PlatformType {
     }
 }
 
-impl Into<&str> for PlatformType {
-    fn into(self) -> &'static str {
-        (&self).into()
+impl From<PlatformType> for &'static str {
+    fn from(platform: PlatformType) -> Self {
+        (&platform).into()
     }
 }
 
 /// Enum representing the different languages
 ///
 /// Defaults to `Language::English`
-#[derive(PartialEq, Eq, Debug, Hash)]
+#[derive(PartialEq, Eq, Debug, Hash, Clone, Copy, Default)]
 pub enum Language {
+    #[default]
     English,
     German,
     Spanish,
@@ -67,9 +210,9 @@ pub enum Language {
     Ukrainian,
 }
 
-impl Into<&str> for &Language {
-    fn into(self) -> &'static str {
-        match self {
+impl From<&Language> for &'static str {
+    fn from(lang: &Language) -> Self {
+        match lang {
             Language::English => "en",
             Language::German => "de",
             Language::Spanish => "es",
@@ -85,25 +228,174 @@ impl Into<&str> for &Language {
     }
 }
 
-impl Into<&str> for Language {
-    fn into(self) -> &'static str {
-        (&self).into()
+impl From<Language> for &'static str {
+    fn from(lang: Language) -> Self {
+        (&lang).into()
     }
 }
 
-impl std::string::ToString for Language {
-    fn to_string(&self) -> String {
+impl std::fmt::Display for Language {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let into: &str = self.into();
-        into.to_string()
+        f.write_str(into)
+    }
+}
+
+key_enum! {
+    ///
+    /// The faction of enemies or of a side in an invasion
+    ///
+    Faction {
+        Grineer => "Grineer",
+        Corpus => "Corpus",
+        Infested => "Infested" | "Infestation",
+        /// the corrupted of the Orokin void
+        Orokin => "Orokin" | "Corrupted",
+        Sentient => "Sentient",
+        Narmer => "Narmer",
+        Murmur => "The Murmur" | "Murmur",
+        /// Grineer and Corpus fighting each other
+        Crossfire => "Crossfire",
+        Tenno => "Tenno",
+        Scaldra => "Scaldra",
+        Techrot => "Techrot",
+    }
+}
+
+key_enum! {
+    MissionType {
+        Alchemy => "Alchemy",
+        Assassination => "Assassination",
+        Assault => "Assault",
+        Capture => "Capture",
+        Defection => "Defection",
+        Defense => "Defense",
+        Disruption => "Disruption",
+        Excavation => "Excavation",
+        Extermination => "Extermination" | "Exterminate",
+        /// the open worlds, e.g. the Plains of Eidolon
+        FreeRoam => "Free Roam",
+        Hijack => "Hijack",
+        Hive => "Hive" | "Hive Sabotage",
+        InfestedSalvage => "Infested Salvage",
+        Interception => "Interception",
+        MirrorDefense => "Mirror Defense",
+        MobileDefense => "Mobile Defense",
+        Netracells => "Netracells",
+        Orphix => "Orphix",
+        Rescue => "Rescue",
+        /// archwing rush, e.g. on Kepler (Phobos)
+        Rush => "Rush",
+        Sabotage => "Sabotage",
+        Skirmish => "Skirmish",
+        Spy => "Spy",
+        Survival => "Survival",
+        VoidArmageddon => "Void Armageddon",
+        VoidCascade => "Void Cascade",
+        VoidFlood => "Void Flood",
+        Volatile => "Volatile",
+    }
+}
+
+key_enum! {
+    ///
+    /// The relic tier of a void fissure
+    ///
+    FissureTier {
+        Lith => "Lith",
+        Meso => "Meso",
+        Neo => "Neo",
+        Axi => "Axi",
+        Requiem => "Requiem",
+        Omnia => "Omnia",
+    }
+}
+
+key_enum! {
+    ///
+    /// The kind of a kuva mission
+    ///
+    KuvaMission {
+        Siphon => "Kuva Siphon" | "Siphon",
+        Flood => "Kuva Flood" | "Flood",
+    }
+}
+
+impl FissureTier {
+    ///
+    /// The tier of a `Fissure::tier_num`, `None` for unknown numbers
+    ///
+    pub fn from_num(tier_num: u8) -> Option<FissureTier> {
+        match tier_num {
+            1 => Some(FissureTier::Lith),
+            2 => Some(FissureTier::Meso),
+            3 => Some(FissureTier::Neo),
+            4 => Some(FissureTier::Axi),
+            5 => Some(FissureTier::Requiem),
+            6 => Some(FissureTier::Omnia),
+            _ => None,
+        }
     }
 }
 
-impl Default for Language {
-    fn default() -> Self {
-        Language::English
+key_enum! {
+    ///
+    /// The state of any open world cycle
+    ///
+    CycleState {
+        Day => "day",
+        Night => "night",
+        Warm => "warm",
+        Cold => "cold",
+        Fass => "fass",
+        Vome => "vome",
+        Corpus => "corpus",
+        Grineer => "grineer",
+        Joy => "joy",
+        Anger => "anger",
+        Envy => "envy",
+        Sorrow => "sorrow",
+        Fear => "fear",
     }
 }
 
+impl Fissure {
+    ///
+    /// The relic tier, taken from the language independent `tier_num`
+    ///
+    pub fn tier_kind(&self) -> FissureTier {
+        FissureTier::from_num(self.tier_num).unwrap_or_else(|| self.tier.as_str().into())
+    }
+}
+
+///
+/// Implements `state_kind` for cycles, taking the state from language independent fields
+///
+macro_rules! impl_state_kind {
+    ($($ty:ty => |$cycle:ident| $state:expr;)*) => {
+        $(
+            impl $ty {
+                ///
+                /// The typed state of the cycle, `state` holds the display string
+                ///
+                pub fn state_kind(&self) -> CycleState {
+                    let $cycle = self;
+                    $state
+                }
+            }
+        )*
+    };
+}
+
+impl_state_kind! {
+    CetusCycle => |cycle| if cycle.is_day { CycleState::Day } else { CycleState::Night };
+    EarthCycle => |cycle| if cycle.is_day { CycleState::Day } else { CycleState::Night };
+    VallisCycle => |cycle| if cycle.is_warm { CycleState::Warm } else { CycleState::Cold };
+    ZarimanCycle => |cycle| if cycle.is_corpus { CycleState::Corpus } else { CycleState::Grineer };
+    CambionCycle => |cycle| cycle.active.as_str().into();
+    DuviriCycle => |cycle| cycle.state.as_str().into();
+}
+
 ///
 /// Requests just about everything, probably better to requests just the stuff you need
 /// Gets cached for only 60s
@@ -263,9 +555,9 @@ pub struct Mission {
     pub node_key: String,
     #[serde(rename = "type")]
     pub type_field: String,
-    pub type_key: String,
+    pub type_key: MissionType,
     pub faction: String,
-    pub faction_key: String,
+    pub faction_key: Faction,
     pub reward: Reward,
     pub min_enemy_level: i64,
     pub max_enemy_level: i64,
@@ -292,7 +584,7 @@ pub struct Sortie {
     pub missions: Vec<Mission>,
     pub boss: String,
     pub faction: String,
-    pub faction_key: String,
+    pub faction_key: Faction,
     pub expired: bool,
     pub eta: String,
 }
@@ -301,7 +593,7 @@ pub struct Sortie {
 #[serde(rename_all = "camelCase")]
 pub struct Variant {
     pub mission_type: String,
-    pub mission_type_key: String,
+    pub mission_type_key: MissionType,
     pub modifier: String,
     pub modifier_description: String,
     pub node: String,
@@ -351,9 +643,9 @@ pub struct Fissure {
     pub active: bool,
     pub node: String,
     pub mission_type: String,
-    pub mission_key: String,
+    pub mission_key: MissionType,
     pub enemy: String,
-    pub enemy_key: String,
+    pub enemy_key: Faction,
     pub node_key: String,
     pub tier: String,
     pub tier_num: u8,
@@ -404,7 +696,7 @@ pub struct Invasion {
 pub struct Attacker {
     pub reward: Option<Reward>,
     pub faction: String,
-    pub faction_key: String,
+    pub faction_key: Faction,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -421,7 +713,7 @@ pub struct CountedItem {
 pub struct Defender {
     pub reward: Reward,
     pub faction: String,
-    pub faction_key: String,
+    pub faction_key: Faction,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -608,7 +900,7 @@ pub struct Arbitration {
     pub sharkwing: bool,
     pub node: String,
     pub node_key: String,
-    pub type_key: String,
+    pub type_key: MissionType,
     pub id: String,
     pub expired: bool,
 }
@@ -719,7 +1011,7 @@ pub struct ArchonHunt {
     pub missions: Vec<ArchonHuntMission>,
     pub boss: String,
     pub faction: String,
-    pub faction_key: String,
+    pub faction_key: Faction,
     pub expired: bool,
     pub eta: String,
 }
@@ -731,7 +1023,7 @@ pub struct ArchonHuntMission {
     pub node_key: String,
     #[serde(rename = "type")]
     pub type_field: String,
-    pub type_key: String,
+    pub type_key: MissionType,
     pub nightmare: bool,
     pub archwing_required: bool,
     pub is_sharkwing: bool,
@@ -768,9 +1060,132 @@ pub struct Kuva {
     pub active: bool,
     pub node: String,
     pub enemy: String,
-    pub enemy_key: String,
+    pub enemy_key: Faction,
     pub kuva_type: String,
-    pub type_key: String,
+    pub type_key: MissionType,
     pub archwing: bool,
     pub sharkwing: bool,
 }
+
+impl Kuva {
+    ///
+    /// Whether this is a kuva siphon or a kuva flood, taken from `kuva_type`
+    ///
+    pub fn kuva_kind(&self) -> KuvaMission {
+        self.kuva_type.as_str().into()
+    }
+}
+
+impl_expirable!(
+    Event,
+    NextAlt,
+    Alert,
+    Sortie,
+    SyndicateMission,
+    Job,
+    Fissure,
+    FlashSale,
+    VoidTrader,
+    DailyDeal,
+    ConclaveChallenge,
+    EarthCycle,
+    CetusCycle,
+    CambionCycle,
+    ZarimanCycle,
+    VallisCycle,
+    Nightwave,
+    ActiveChallenge,
+    Arbitration,
+    SentientOutposts,
+    SteelPath,
+    Incursions,
+    VaultTrader,
+    Schedule,
+    ArchonHunt,
+    DuviriCycle,
+    Kuva,
+);
+
+impl_earliest_expiry!(
+    Event,
+    Alert,
+    Sortie,
+    SyndicateMission,
+    Fissure,
+    FlashSale,
+    DailyDeal,
+    ConclaveChallenge,
+    EarthCycle,
+    CetusCycle,
+    CambionCycle,
+    ZarimanCycle,
+    VallisCycle,
+    ActiveChallenge,
+    Arbitration,
+    SentientOutposts,
+    Incursions,
+    VaultTrader,
+    ArchonHunt,
+    DuviriCycle,
+    Kuva,
+);
+
+impl_no_expiry!(News, Invasion, Simaris, ConstructionProgress);
+
+impl EarliestExpiry for VoidTrader {
+    /// Baro changes when he arrives, not only when he leaves
+    fn earliest_expiry(&self) -> Option<DateTime<Local>> {
+        if self.active {
+            Some(self.expiry)
+        } else {
+            Some(self.activation)
+        }
+    }
+}
+
+impl EarliestExpiry for Nightwave {
+    /// The season lasts for months but its challenges rotate daily
+    fn earliest_expiry(&self) -> Option<DateTime<Local>> {
+        let challenges = self.active_challenges.earliest_expiry();
+        Some(challenges.map_or(self.expiry, |challenges| challenges.min(self.expiry)))
+    }
+}
+
+impl EarliestExpiry for SteelPath {
+    fn earliest_expiry(&self) -> Option<DateTime<Local>> {
+        Some(self.expiry.min(self.incursions.expiry))
+    }
+}
+
+impl EarliestExpiry for Platform {
+    fn earliest_expiry(&self) -> Option<DateTime<Local>> {
+        [
+            self.events.earliest_expiry(),
+            self.alerts.earliest_expiry(),
+            self.sortie.earliest_expiry(),
+            self.syndicate_missions.earliest_expiry(),
+            self.fissures.earliest_expiry(),
+            self.flash_sales.earliest_expiry(),
+            self.void_trader.earliest_expiry(),
+            self.daily_deals.earliest_expiry(),
+            self.conclave_challenges.earliest_expiry(),
+            self.earth_cycle.earliest_expiry(),
+            self.cetus_cycle.earliest_expiry(),
+            self.cambion_cycle.earliest_expiry(),
+            self.zariman_cycle.earliest_expiry(),
+            self.vallis_cycle.earliest_expiry(),
+            self.nightwave.earliest_expiry(),
+            self.kuva.earliest_expiry(),
+            self.arbitration.earliest_expiry(),
+            self.sentient_outposts.earliest_expiry(),
+            self.steel_path.earliest_expiry(),
+            self.vault_trader.earliest_expiry(),
+            self.archon_hunt.earliest_expiry(),
+            self.duviri_cycle.earliest_expiry(),
+        ]
+        .into_iter()
+        .flatten()
+        .filter(|expiry| *expiry > Local::now())
+        .min()
+    }
+}
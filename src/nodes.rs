@@ -0,0 +1,192 @@
+//!
+//! Star chart nodes, resolving `node`/`node_key` strings like "Hydron (Sedna)"
+//!
+//! A dataset of nodes is bundled with the crate, see `NodeDatabase::bundled`. It covers the
+//! mission nodes of every planet, the Railjack proxima nodes and the Zariman, relays and event
+//! nodes aren't included. It can be extended or replaced with a json file of the same format.
+//!
+use crate::error::{self, from_json, WarframeError};
+use crate::model::{Faction, MissionType};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, OnceLock};
+
+const BUNDLED_NODES: &str = include_str!("../resources/sol_nodes.json");
+
+///
+/// A node of the star chart
+///
+/// The level range is the one of the normal star chart, without Steel Path
+///
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Node {
+    /// e.g. "Hydron (Sedna)"
+    pub key: String,
+    pub name: String,
+    pub planet: String,
+    pub mission_type: MissionType,
+    pub faction: Faction,
+    pub min_level: u32,
+    pub max_level: u32,
+    pub dark_sector: bool,
+    pub railjack: bool,
+    pub zariman: bool,
+}
+
+///
+/// Nodes by key, keys are compared ignoring case and spacing
+///
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct NodeDatabase {
+    nodes: HashMap<String, Node>,
+}
+
+impl NodeDatabase {
+    pub fn new(nodes: Vec<Node>) -> Self {
+        let mut database = Self::default();
+        database.extend(nodes);
+        database
+    }
+
+    ///
+    /// The dataset bundled with the crate
+    ///
+    /// Relays and event nodes aren't included, `get` returns `None` for them
+    ///
+    pub fn bundled() -> &'static NodeDatabase {
+        static BUNDLED: OnceLock<NodeDatabase> = OnceLock::new();
+        BUNDLED.get_or_init(|| {
+            NodeDatabase::from_json(BUNDLED_NODES).expect("the bundled nodes are valid")
+        })
+    }
+
+    ///
+    /// Parse a json array of nodes, e.g. `resources/sol_nodes.json`
+    ///
+    pub fn from_json(json: &str) -> error::Result<Self> {
+        Ok(Self::new(from_json(json)?))
+    }
+
+    ///
+    /// Load a json array of nodes from a file
+    ///
+    pub async fn load(path: impl AsRef<Path>) -> error::Result<Self> {
+        let json = tokio::fs::read_to_string(path)
+            .await
+            .map_err(|err| WarframeError::Io(Arc::new(err)))?;
+        Self::from_json(&json)
+    }
+
+    ///
+    /// Add nodes, replacing nodes with the same key
+    ///
+    pub fn extend(&mut self, nodes: impl IntoIterator<Item = Node>) {
+        self.nodes
+            .extend(nodes.into_iter().map(|node| (normalize(&node.key), node)));
+    }
+
+    ///
+    /// The node of a `node`/`node_key` like "Hydron (Sedna)"
+    ///
+    pub fn get(&self, key: &str) -> Option<&Node> {
+        self.nodes.get(&normalize(key))
+    }
+
+    pub fn nodes(&self) -> impl Iterator<Item = &Node> {
+        self.nodes.values()
+    }
+
+    ///
+    /// Every node of `planet`, ignoring case
+    ///
+    pub fn on_planet<'a>(&'a self, planet: &'a str) -> impl Iterator<Item = &'a Node> {
+        self.nodes()
+            .filter(move |node| node.planet.eq_ignore_ascii_case(planet.trim()))
+    }
+}
+
+///
+/// Split a node key like "Hydron (Sedna)" into its name and planet
+///
+/// Works for nodes missing from the database too, e.g. to filter fissures by planet
+///
+pub fn split_key(key: &str) -> Option<(&str, &str)> {
+    let (name, planet) = key.trim().strip_suffix(')')?.rsplit_once('(')?;
+    Some((name.trim(), planet.trim()))
+}
+
+fn normalize(key: &str) -> String {
+    key.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{split_key, NodeDatabase};
+    use crate::model::*;
+    use std::fs;
+    use std::path::PathBuf;
+
+    #[test]
+    pub fn test_node_database() -> Result<(), Box<dyn std::error::Error>> {
+        let nodes = NodeDatabase::bundled();
+        let hydron = nodes.get("hydron  (sedna)").unwrap();
+        assert_eq!(hydron.planet, "Sedna");
+        assert_eq!(hydron.name, "Hydron");
+        assert_eq!(hydron.mission_type, MissionType::Defense);
+        assert_eq!(hydron.faction, Faction::Grineer);
+        assert!(nodes.get("Sechura (Pluto)").unwrap().dark_sector);
+        assert!(nodes.on_planet("zariman").all(|node| node.zariman));
+        assert!(nodes.on_planet("Sedna").count() >= 15);
+        assert!(nodes.on_planet("Mercury").count() >= 10);
+        assert!(nodes
+            .nodes()
+            .all(|node| !matches!(node.mission_type, MissionType::Unknown(_))));
+        assert_eq!(
+            split_key("Nu-gua Mines (Neptune)"),
+            Some(("Nu-gua Mines", "Neptune"))
+        );
+        assert_eq!(split_key("Orb Vallis"), None);
+
+        let mut test_json = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_json.push("resources/test/pc_test.json");
+        let platform: Platform = serde_json::from_str(&fs::read_to_string(test_json)?)?;
+        for fissure in &platform.fissures {
+            let node = nodes.get(&fissure.node_key).unwrap();
+            assert_eq!(node.mission_type, fissure.mission_key);
+            assert_eq!(node.railjack, fissure.is_storm);
+        }
+        let arbitration = nodes.get(&platform.arbitration.node_key).unwrap();
+        assert_eq!(arbitration.mission_type, platform.arbitration.type_key);
+
+        let keys = platform
+            .fissures
+            .iter()
+            .map(|fissure| &fissure.node_key)
+            .chain(platform.alerts.iter().map(|alert| &alert.mission.node_key))
+            .chain(platform.invasions.iter().map(|invasion| &invasion.node_key))
+            .chain(
+                platform
+                    .sortie
+                    .variants
+                    .iter()
+                    .map(|variant| &variant.node_key),
+            )
+            .chain(
+                platform
+                    .archon_hunt
+                    .missions
+                    .iter()
+                    .map(|mission| &mission.node_key),
+            )
+            .chain(std::iter::once(&platform.arbitration.node_key));
+        for key in keys {
+            assert!(nodes.get(key).is_some(), "missing node {key}");
+        }
+        Ok(())
+    }
+}
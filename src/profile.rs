@@ -0,0 +1,172 @@
+//!
+//! Public player profiles, looked up by username
+//!
+use crate::error::{self, WarframeError};
+use crate::model::PlatformType;
+use crate::transport::Transport;
+use crate::WarframeClient;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+///
+/// A player's public profile
+///
+/// Optional parts of the profile are defaulted when the api leaves them out
+///
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Profile {
+    pub account_id: String,
+    pub display_name: String,
+    pub mastery_rank: i64,
+    pub player_level: i64,
+    pub created: Option<DateTime<Local>>,
+    pub guild_id: Option<String>,
+    pub guild_name: Option<String>,
+    pub guild_tier: Option<i64>,
+    pub daily_standing: i64,
+    pub daily_focus: i64,
+    pub is_online: bool,
+    pub loadout: Loadout,
+    pub syndicates: Vec<SyndicateStanding>,
+    pub stats: Stats,
+}
+
+///
+/// The gear a player currently has equipped
+///
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Loadout {
+    pub warframe: Option<LoadoutItem>,
+    pub primary: Option<LoadoutItem>,
+    pub secondary: Option<LoadoutItem>,
+    pub melee: Option<LoadoutItem>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct LoadoutItem {
+    pub item_id: String,
+    pub unique_name: String,
+    pub name: String,
+    pub xp: i64,
+    /// how often the item has been forma'd
+    pub polarized: i64,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SyndicateStanding {
+    pub name: String,
+    pub standing: i64,
+    /// negative for syndicates the player is an enemy of
+    pub rank: i64,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Stats {
+    pub xp: i64,
+    pub missions_completed: i64,
+    pub missions_quit: i64,
+    pub missions_failed: i64,
+    pub missions_interrupted: i64,
+    pub missions_dumped: i64,
+    pub pickup_count: i64,
+    pub time_played_sec: f64,
+    pub deaths: i64,
+    pub melee_kills: i64,
+    pub income: i64,
+    pub rating: i64,
+    pub ciphers_solved: i64,
+    pub ciphers_failed: i64,
+}
+
+impl Profile {
+    ///
+    /// The standing with a syndicate, ignoring case
+    ///
+    pub fn syndicate(&self, name: &str) -> Option<&SyndicateStanding> {
+        self.syndicates
+            .iter()
+            .find(|syndicate| syndicate.name.eq_ignore_ascii_case(name.trim()))
+    }
+}
+
+impl<T: Transport> WarframeClient<T> {
+    ///
+    /// Get the public profile of the player `username`
+    ///
+    /// Fails with `WarframeError::PlayerNotFound` if there's no such player on `platform`
+    ///
+    pub async fn get_profile(
+        &self,
+        username: &str,
+        platform: impl Into<Option<PlatformType>>,
+    ) -> error::Result<Profile> {
+        let platform = platform.into().unwrap_or(self.platform);
+        let username = username.trim();
+        let not_found = || WarframeError::PlayerNotFound {
+            username: username.to_string(),
+            platform,
+        };
+        if username.is_empty() {
+            return Err(not_found());
+        }
+        let url = self.url(
+            &["profile", username],
+            &[("platform", platform.into())],
+            self.language,
+        );
+        let key = format!("{}?{}", url.path(), url.query().unwrap_or_default());
+        let profile = self.get_cached(key, &url, platform, self.language, |_: &Profile| {
+            self.cache_policy.expiration_time(None)
+        });
+        match profile.await {
+            Err(WarframeError::NotFound { .. }) => Err(not_found()),
+            res => res,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::model::*;
+    use crate::transport::FixtureTransport;
+    use crate::{WarframeClient, WarframeError};
+    use std::fs;
+    use std::path::PathBuf;
+
+    #[tokio::test]
+    pub async fn test_get_profile() -> Result<(), Box<dyn std::error::Error>> {
+        let mut test_json = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_json.push("resources/test/profile_test.json");
+        let fixtures =
+            FixtureTransport::new().with_json("/profile/Tenno42", fs::read_to_string(test_json)?);
+        let client = WarframeClient::builder().build_with_transport(fixtures.clone())?;
+
+        let profile = client.get_profile("Tenno42", PlatformType::XBox).await?;
+        assert_eq!(profile.mastery_rank, 27);
+        assert_eq!(
+            profile.loadout.warframe.as_ref().unwrap().name,
+            "Excalibur Prime"
+        );
+        assert_eq!(profile.loadout.secondary, None);
+        assert_eq!(profile.syndicate("ostrons").unwrap().rank, 5);
+        assert_eq!(profile.stats.missions_completed, 5321);
+        assert_eq!(
+            fixtures.requests()[0].0.query(),
+            Some("language=en&platform=xb1")
+        );
+
+        match client.get_profile(" nobody ", None).await {
+            Err(WarframeError::PlayerNotFound { username, platform }) => {
+                assert_eq!(username, "nobody");
+                assert_eq!(platform, PlatformType::PC);
+            }
+            res => panic!("expected PlayerNotFound, got {:?}", res),
+        }
+        Ok(())
+    }
+}
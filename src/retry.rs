@@ -0,0 +1,154 @@
+use crate::error::{TransportErrorKind, WarframeError};
+use chrono::{DateTime, Utc};
+use rand::Rng;
+use reqwest::header::HeaderValue;
+use reqwest::StatusCode;
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+///
+/// Information about a retry that is about to happen, passed to `RetryPolicy::on_retry`
+///
+#[derive(Debug)]
+pub struct RetryEvent<'a> {
+    /// The attempt that just failed, starting at 1
+    pub attempt: u32,
+    /// How long the client waits before the next attempt
+    pub delay: Duration,
+    /// Why the attempt failed
+    pub error: &'a WarframeError,
+}
+
+type RetryHook = Arc<dyn Fn(&RetryEvent<'_>) + Send + Sync>;
+
+///
+/// Defines how a `WarframeClient` retries requests that failed with a transient error
+///
+/// Only the client's own GET requests are retried, and only if they failed because of a
+/// timeout, a connection error, `429 Too Many Requests` or a `502`/`503`/`504`. The delay
+/// grows exponentially from `base_delay` up to `max_delay`, a `Retry-After` sent by the api
+/// takes precedence if it's longer. A `Retry-After` longer than `max_delay` isn't waited for,
+/// the request fails with `WarframeError::RateLimited` instead.
+///
+/// A `WebhookDispatcher` only retries posts that failed with `429 Too Many Requests`, a post
+/// isn't idempotent.
+///
+/// Defaults to 3 attempts, starting with a 500ms delay
+///
+#[derive(Clone)]
+pub struct RetryPolicy {
+    /// Maximum number of attempts including the first one, `1` disables retries
+    pub max_attempts: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+    /// Randomize each delay between half and the full backoff
+    pub jitter: bool,
+    on_retry: Option<RetryHook>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(30),
+            jitter: true,
+            on_retry: None,
+        }
+    }
+}
+
+impl fmt::Debug for RetryPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RetryPolicy")
+            .field("max_attempts", &self.max_attempts)
+            .field("base_delay", &self.base_delay)
+            .field("max_delay", &self.max_delay)
+            .field("jitter", &self.jitter)
+            .field("on_retry", &self.on_retry.is_some())
+            .finish()
+    }
+}
+
+impl RetryPolicy {
+    ///
+    /// Never retry
+    ///
+    pub fn none() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Self::default()
+        }
+    }
+
+    ///
+    /// Call `hook` before every retry, e.g. for logging or metrics
+    ///
+    pub fn on_retry(mut self, hook: impl Fn(&RetryEvent<'_>) + Send + Sync + 'static) -> Self {
+        self.on_retry = Some(Arc::new(hook));
+        self
+    }
+
+    ///
+    /// Returns how long to wait before the next attempt or `None` if `error` shouldn't be retried
+    ///
+    /// Never returns more than `max_delay`
+    ///
+    pub fn delay_for(&self, attempt: u32, error: &WarframeError) -> Option<Duration> {
+        if attempt >= self.max_attempts || !is_transient(error) {
+            return None;
+        }
+        let exponent = attempt.saturating_sub(1).min(31);
+        let mut delay = self
+            .base_delay
+            .saturating_mul(1 << exponent)
+            .min(self.max_delay);
+        if self.jitter && !delay.is_zero() {
+            delay = rand::thread_rng().gen_range(delay / 2..=delay);
+        }
+        match error {
+            WarframeError::RateLimited {
+                retry_after: Some(retry_after),
+            } if *retry_after > self.max_delay => None,
+            WarframeError::RateLimited {
+                retry_after: Some(retry_after),
+            } => Some(delay.max(*retry_after)),
+            _ => Some(delay),
+        }
+    }
+
+    pub(crate) fn notify(&self, event: &RetryEvent<'_>) {
+        if let Some(hook) = &self.on_retry {
+            hook(event);
+        }
+    }
+}
+
+///
+/// Whether retrying might fix `error`
+///
+pub fn is_transient(error: &WarframeError) -> bool {
+    match error {
+        WarframeError::Transport(err) => err.is_timeout() || err.is_connect(),
+        WarframeError::CustomTransport { kind, .. } => *kind != TransportErrorKind::Other,
+        WarframeError::RateLimited { .. } => true,
+        WarframeError::Status { status, .. } => matches!(
+            *status,
+            StatusCode::BAD_GATEWAY | StatusCode::SERVICE_UNAVAILABLE | StatusCode::GATEWAY_TIMEOUT
+        ),
+        _ => false,
+    }
+}
+
+///
+/// Parse a `Retry-After` header, which is either a number of seconds or an http date
+///
+pub(crate) fn parse_retry_after(value: &HeaderValue) -> Option<Duration> {
+    let value = value.to_str().ok()?.trim();
+    if let Ok(secs) = value.parse() {
+        return Some(Duration::from_secs(secs));
+    }
+    let date = DateTime::parse_from_rfc2822(value).ok()?;
+    (date.with_timezone(&Utc) - Utc::now()).to_std().ok()
+}
@@ -0,0 +1,141 @@
+//!
+//! Weekly riven trade statistics, grouped by riven type and weapon
+//!
+use crate::error;
+use crate::model::PlatformType;
+use crate::transport::Transport;
+use crate::WarframeClient;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+///
+/// Trade price statistics of rivens for one weapon, either rerolled or unrolled
+///
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RivenStat {
+    pub item_type: String,
+    /// the weapon, `None` for veiled rivens
+    pub compatibility: Option<String>,
+    pub rerolled: bool,
+    pub avg: f64,
+    pub stddev: f64,
+    pub min: f64,
+    pub max: f64,
+    /// popularity in percent
+    pub pop: f64,
+    pub median: f64,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct WeaponRivenStats {
+    pub rerolled: Option<RivenStat>,
+    pub unrolled: Option<RivenStat>,
+}
+
+///
+/// Riven statistics by riven type (e.g. "Rifle") and weapon (e.g. "Soma Prime")
+///
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RivenStats(HashMap<String, HashMap<String, WeaponRivenStats>>);
+
+impl RivenStats {
+    ///
+    /// The statistics of a weapon, ignoring case and spacing, e.g. "somaprime" finds "Soma Prime"
+    ///
+    pub fn weapon(&self, name: &str) -> Option<&WeaponRivenStats> {
+        let name = normalize(name);
+        self.weapons()
+            .find(|(weapon, _)| normalize(weapon) == name)
+            .map(|(_, stats)| stats)
+    }
+
+    ///
+    /// Every weapon with its statistics
+    ///
+    pub fn weapons(&self) -> impl Iterator<Item = (&str, &WeaponRivenStats)> {
+        self.0
+            .values()
+            .flatten()
+            .map(|(weapon, stats)| (weapon.as_str(), stats))
+    }
+
+    ///
+    /// The weapons of a riven type, e.g. "Pistol", ignoring case and spacing
+    ///
+    pub fn item_type(&self, item_type: &str) -> Option<&HashMap<String, WeaponRivenStats>> {
+        let item_type = normalize(item_type);
+        self.0
+            .iter()
+            .find(|(name, _)| normalize(name) == item_type)
+            .map(|(_, weapons)| weapons)
+    }
+}
+
+fn normalize(name: &str) -> String {
+    name.chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+impl<T: Transport> WarframeClient<T> {
+    ///
+    /// Get the riven trade statistics of `platform`
+    ///
+    /// They're only updated weekly, so they're cached for `CachePolicy::static_ttl`
+    ///
+    pub async fn get_rivens(
+        &self,
+        platform: impl Into<Option<PlatformType>>,
+    ) -> error::Result<RivenStats> {
+        let platform = platform.into().unwrap_or(self.platform);
+        let url = self.section_url("rivens", platform, self.language);
+        self.get_cached(
+            "rivens".to_string(),
+            &url,
+            platform,
+            self.language,
+            |_: &RivenStats| self.cache_policy.static_expiration_time(),
+        )
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::transport::FixtureTransport;
+    use crate::WarframeClient;
+
+    #[tokio::test]
+    pub async fn test_get_rivens() -> Result<(), Box<dyn std::error::Error>> {
+        let rivens = r#"{
+            "Rifle": {
+                "Soma Prime": {
+                    "rerolled": {"itemType": "Rifle Riven Mod", "compatibility": "Soma Prime", "rerolled": true, "avg": 52.3, "stddev": 40.1, "min": 10, "max": 250, "pop": 1.2, "median": 40},
+                    "unrolled": {"itemType": "Rifle Riven Mod", "compatibility": "Soma Prime", "rerolled": false, "avg": 30.5, "stddev": 12.2, "min": 15, "max": 60, "pop": 0.4, "median": 28}
+                }
+            },
+            "Pistol": {
+                "Kuva Nukor": {
+                    "unrolled": {"itemType": "Pistol Riven Mod", "compatibility": "Kuva Nukor", "rerolled": false, "avg": 120, "stddev": 80, "min": 40, "max": 500, "pop": 3.4, "median": 100}
+                }
+            }
+        }"#;
+        let fixtures = FixtureTransport::new().with_json("/pc/rivens", rivens);
+        let client = WarframeClient::builder().build_with_transport(fixtures.clone())?;
+
+        let stats = client.get_rivens(None).await?;
+        let soma = stats.weapon("  somaprime").unwrap();
+        assert_eq!(soma.rerolled.as_ref().unwrap().max, 250.0);
+        assert_eq!(soma.unrolled.as_ref().unwrap().median, 28.0);
+        assert!(stats.weapon("KUVA NUKOR").unwrap().rerolled.is_none());
+        assert_eq!(stats.item_type("pistol").unwrap().len(), 1);
+        assert_eq!(stats.weapons().count(), 2);
+
+        client.get_rivens(None).await?;
+        assert_eq!(fixtures.requests().len(), 1);
+        Ok(())
+    }
+}
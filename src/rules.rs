@@ -0,0 +1,277 @@
+//!
+//! Notification rules matched against a worldstate or its change events
+//!
+//! Rules are plain serde types, so users' subscriptions can be stored as JSON
+//!
+use crate::diff::WorldstateEvent;
+use crate::model::*;
+use serde::{Deserialize, Serialize};
+
+///
+/// A named subscription that matches when all of its conditions hold for the same entity
+///
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Rule {
+    pub id: String,
+    pub name: String,
+    pub conditions: Vec<Condition>,
+}
+
+///
+/// A single check of a rule, text comparisons ignore case
+///
+/// Conditions that don't apply to an entity, like a fissure tier on an invasion, never hold
+///
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(
+    tag = "kind",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum Condition {
+    /// `Fissure.tier` equals `tier`, e.g. "Axi"
+    FissureTier { tier: String },
+    /// Steel Path fissures
+    FissureHard { is_hard: bool },
+    /// Void Storms (Railjack fissures)
+    FissureStorm { is_storm: bool },
+    /// the mission type of a fissure or alert equals `mission_type`, e.g. "Survival"
+    MissionType { mission_type: String },
+    /// an item in `Reward.items` or `Reward.counted_items` of an alert or invasion contains `item`
+    Reward { item: String },
+    /// the open world `cycle` is in `state`, e.g. "night"
+    CycleState { cycle: Cycle, state: String },
+    /// Baro Ki'Teer sells an item containing `item`
+    VoidTraderItem { item: String },
+}
+
+///
+/// The open world cycles a `Condition::CycleState` can refer to
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Cycle {
+    Cetus,
+    Vallis,
+    Cambion,
+    Zariman,
+    Earth,
+    Duviri,
+}
+
+///
+/// An entity rules are evaluated against
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Subject<'a> {
+    Fissure(&'a Fissure),
+    Alert(&'a Alert),
+    Invasion(&'a Invasion),
+    VoidTrader(&'a VoidTrader),
+    Cycle(Cycle, &'a str),
+}
+
+///
+/// A rule that matched and the entity it matched
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RuleMatch<'a> {
+    pub rule: &'a Rule,
+    pub subject: Subject<'a>,
+}
+
+impl Rule {
+    ///
+    /// Whether every condition holds for `subject`, a rule without conditions never matches
+    ///
+    pub fn matches(&self, subject: Subject) -> bool {
+        !self.conditions.is_empty() && self.conditions.iter().all(|c| c.matches(subject))
+    }
+}
+
+impl Condition {
+    ///
+    /// Whether the condition holds for `subject`
+    ///
+    pub fn matches(&self, subject: Subject) -> bool {
+        match (self, subject) {
+            (Condition::FissureTier { tier }, Subject::Fissure(f)) => {
+                f.tier_kind() == FissureTier::from(tier.as_str()) || eq(&f.tier, tier)
+            }
+            (Condition::FissureHard { is_hard }, Subject::Fissure(f)) => f.is_hard == *is_hard,
+            (Condition::FissureStorm { is_storm }, Subject::Fissure(f)) => f.is_storm == *is_storm,
+            (Condition::MissionType { mission_type }, Subject::Fissure(f)) => {
+                f.mission_key == MissionType::from(mission_type.as_str())
+                    || eq(&f.mission_type, mission_type)
+            }
+            (Condition::MissionType { mission_type }, Subject::Alert(a)) => {
+                a.mission.type_key == MissionType::from(mission_type.as_str())
+                    || eq(&a.mission.type_field, mission_type)
+            }
+            (Condition::Reward { item }, Subject::Alert(a)) => has_item(&a.mission.reward, item),
+            (Condition::Reward { item }, Subject::Invasion(i)) => {
+                i.attacker.reward.iter().any(|r| has_item(r, item))
+                    || has_item(&i.defender.reward, item)
+            }
+            (Condition::CycleState { cycle, state }, Subject::Cycle(current, current_state)) => {
+                *cycle == current && eq(current_state, state)
+            }
+            (Condition::VoidTraderItem { item }, Subject::VoidTrader(trader)) => {
+                trader.inventory.iter().any(|i| contains(&i.item, item))
+            }
+            _ => false,
+        }
+    }
+}
+
+impl<'a> Subject<'a> {
+    ///
+    /// Everything currently active in `platform`
+    ///
+    pub fn from_platform(platform: &'a Platform) -> Vec<Subject<'a>> {
+        let mut subjects: Vec<_> = platform.fissures.iter().map(Subject::Fissure).collect();
+        subjects.extend(platform.alerts.iter().map(Subject::Alert));
+        subjects.extend(
+            platform
+                .invasions
+                .iter()
+                .filter(|i| !i.completed)
+                .map(Subject::Invasion),
+        );
+        if platform.void_trader.active {
+            subjects.push(Subject::VoidTrader(&platform.void_trader));
+        }
+        subjects.extend([
+            Subject::Cycle(Cycle::Cetus, &platform.cetus_cycle.state),
+            Subject::Cycle(Cycle::Vallis, &platform.vallis_cycle.state),
+            Subject::Cycle(Cycle::Cambion, &platform.cambion_cycle.state),
+            Subject::Cycle(Cycle::Zariman, &platform.zariman_cycle.state),
+            Subject::Cycle(Cycle::Earth, &platform.earth_cycle.state),
+            Subject::Cycle(Cycle::Duviri, &platform.duviri_cycle.state),
+        ]);
+        subjects
+    }
+
+    ///
+    /// The entity that started with `event`, `None` for events that end or rotate something
+    ///
+    pub fn from_event(event: &'a WorldstateEvent) -> Option<Subject<'a>> {
+        use WorldstateEvent::*;
+
+        Some(match event {
+            FissureStarted(fissure) => Subject::Fissure(fissure),
+            AlertStarted(alert) => Subject::Alert(alert),
+            InvasionStarted(invasion) => Subject::Invasion(invasion),
+            VoidTraderArrived(trader) => Subject::VoidTrader(trader),
+            CetusCycleChanged(cycle) => Subject::Cycle(Cycle::Cetus, &cycle.state),
+            VallisCycleChanged(cycle) => Subject::Cycle(Cycle::Vallis, &cycle.state),
+            CambionCycleChanged(cycle) => Subject::Cycle(Cycle::Cambion, &cycle.state),
+            ZarimanCycleChanged(cycle) => Subject::Cycle(Cycle::Zariman, &cycle.state),
+            EarthCycleChanged(cycle) => Subject::Cycle(Cycle::Earth, &cycle.state),
+            DuviriCycleChanged(cycle) => Subject::Cycle(Cycle::Duviri, &cycle.state),
+            _ => return None,
+        })
+    }
+}
+
+///
+/// Every rule matching something currently active in `platform`
+///
+pub fn evaluate_platform<'a>(rules: &'a [Rule], platform: &'a Platform) -> Vec<RuleMatch<'a>> {
+    evaluate(rules, Subject::from_platform(platform))
+}
+
+///
+/// Every rule matching something that started with `events`, e.g. "Cetus night starts"
+///
+pub fn evaluate_events<'a>(rules: &'a [Rule], events: &'a [WorldstateEvent]) -> Vec<RuleMatch<'a>> {
+    evaluate(rules, events.iter().filter_map(Subject::from_event))
+}
+
+fn evaluate<'a>(
+    rules: &'a [Rule],
+    subjects: impl IntoIterator<Item = Subject<'a>>,
+) -> Vec<RuleMatch<'a>> {
+    subjects
+        .into_iter()
+        .flat_map(|subject| {
+            rules
+                .iter()
+                .filter(move |rule| rule.matches(subject))
+                .map(move |rule| RuleMatch { rule, subject })
+        })
+        .collect()
+}
+
+fn has_item(reward: &Reward, item: &str) -> bool {
+    reward.items.iter().any(|i| contains(i, item))
+        || reward
+            .counted_items
+            .iter()
+            .any(|i| contains(&i.type_field, item) || contains(&i.key, item))
+}
+
+fn eq(value: &str, expected: &str) -> bool {
+    value.trim().eq_ignore_ascii_case(expected.trim())
+}
+
+fn contains(value: &str, expected: &str) -> bool {
+    value
+        .to_lowercase()
+        .contains(&expected.trim().to_lowercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{evaluate_events, evaluate_platform, Condition, Cycle, Rule, Subject};
+    use crate::model::*;
+    use std::fs;
+    use std::path::PathBuf;
+
+    #[test]
+    pub fn test_rules() -> Result<(), Box<dyn std::error::Error>> {
+        let mut test_json = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_json.push("resources/test/pc_test.json");
+        let platform: Platform = serde_json::from_str(&fs::read_to_string(test_json)?)?;
+
+        let rules: Vec<Rule> = serde_json::from_str(
+            r#"[
+                {"id": "neo", "name": "Neo Steel Path defense", "conditions": [
+                    {"kind": "fissureTier", "tier": "neo"},
+                    {"kind": "fissureHard", "isHard": true},
+                    {"kind": "missionType", "missionType": "Defense"}
+                ]},
+                {"id": "dera", "name": "Dera Vandal", "conditions": [
+                    {"kind": "reward", "item": "dera vandal"}
+                ]},
+                {"id": "night", "name": "Cetus night", "conditions": [
+                    {"kind": "cycleState", "cycle": "cetus", "state": "night"}
+                ]}
+            ]"#,
+        )?;
+        assert_eq!(
+            rules[2].conditions[0],
+            Condition::CycleState {
+                cycle: Cycle::Cetus,
+                state: "night".to_string()
+            }
+        );
+
+        let matches = evaluate_platform(&rules, &platform);
+        let ids: Vec<_> = matches.iter().map(|m| m.rule.id.as_str()).collect();
+        assert!(ids.contains(&"neo") && ids.contains(&"dera"));
+        assert!(!ids.contains(&"night"));
+        assert!(matches
+            .iter()
+            .any(|m| m.subject == Subject::Fissure(&platform.fissures[0])));
+
+        let mut night = platform.clone();
+        night.cetus_cycle.state = "night".to_string();
+        let events = platform.diff(&night);
+        let matches = evaluate_events(&rules, &events);
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].rule.id, "night");
+        Ok(())
+    }
+}
@@ -0,0 +1,86 @@
+use crate::error::{from_value, Result, WarframeError};
+use crate::model::{Language, Platform, PlatformType};
+use serde::de::DeserializeOwned;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+///
+/// A directory of saved worldstates that a `WarframeClient` can serve its getters from instead
+/// of the api, see `WarframeClientBuilder::snapshot`
+///
+/// Worldstates are looked up as `<dir>/<platform>/<language>.json`, falling back to
+/// `<dir>/<platform>.json` for any language, e.g. `snapshots/pc/de.json` or `snapshots/pc.json`.
+/// Each file holds the whole worldstate as returned by `WarframeClient::get_platform`.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snapshot {
+    dir: PathBuf,
+}
+
+impl Snapshot {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    ///
+    /// Save a worldstate, so it's served for `platform` and `lang` from now on
+    ///
+    pub async fn save(&self, platform: PlatformType, lang: Language, pl: &Platform) -> Result<()> {
+        let dir = self.dir.join(platform.to_string());
+        let content = serde_json::to_vec(pl).map_err(|err| io_error(err.into()))?;
+        tokio::fs::create_dir_all(&dir).await.map_err(io_error)?;
+        tokio::fs::write(dir.join(format!("{}.json", lang)), content)
+            .await
+            .map_err(io_error)
+    }
+
+    ///
+    /// Read a section of the saved worldstate, `path` being its camelCase name like in the api
+    ///
+    pub(crate) async fn section<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        platform: PlatformType,
+        lang: Language,
+    ) -> Result<T> {
+        let (file, content) = self.read(platform, lang).await?;
+        let worldstate: serde_json::Value = crate::error::from_json(&content)?;
+        if path.is_empty() {
+            return from_value(&worldstate);
+        }
+        match worldstate.get(path) {
+            Some(section) => from_value(section),
+            None => Err(WarframeError::NotFound {
+                url: format!("{}#{}", file.display(), path),
+            }),
+        }
+    }
+
+    async fn read(&self, platform: PlatformType, lang: Language) -> Result<(PathBuf, String)> {
+        let candidates = [
+            self.dir
+                .join(platform.to_string())
+                .join(format!("{}.json", lang)),
+            self.dir.join(format!("{}.json", platform)),
+        ];
+        for file in candidates {
+            match tokio::fs::read_to_string(&file).await {
+                Ok(content) => return Ok((file, content)),
+                Err(err) if err.kind() == ErrorKind::NotFound => continue,
+                Err(err) => return Err(io_error(err)),
+            }
+        }
+        Err(WarframeError::NotFound {
+            url: self.dir.join(platform.to_string()).display().to_string(),
+        })
+    }
+}
+
+fn io_error(err: std::io::Error) -> WarframeError {
+    WarframeError::Io(Arc::new(err))
+}
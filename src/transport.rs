@@ -0,0 +1,170 @@
+use crate::error::Result;
+use async_trait::async_trait;
+use reqwest::header::HeaderMap;
+use reqwest::{Client as HttpClient, StatusCode};
+use std::collections::{HashMap, VecDeque};
+use std::fmt::Debug;
+use std::sync::{Arc, Mutex};
+use url::Url;
+
+///
+/// A response as returned by a `Transport`
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransportResponse {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub body: String,
+}
+
+impl TransportResponse {
+    pub fn new(status: StatusCode, body: impl Into<String>) -> Self {
+        Self {
+            status,
+            headers: HeaderMap::new(),
+            body: body.into(),
+        }
+    }
+
+    ///
+    /// `200 OK` with `body`
+    ///
+    pub fn ok(body: impl Into<String>) -> Self {
+        Self::new(StatusCode::OK, body)
+    }
+
+    pub fn with_header(mut self, name: &'static str, value: &str) -> Self {
+        if let Ok(value) = value.parse() {
+            self.headers.insert(name, value);
+        }
+        self
+    }
+}
+
+///
+/// Performs the GET requests of a `WarframeClient`
+///
+/// Implement this to run the client against something else than the network, e.g. the
+/// `FixtureTransport` in tests
+///
+#[async_trait]
+pub trait Transport: Debug + Send + Sync {
+    ///
+    /// GET `url` with the additional `headers`, the response body is read completely
+    ///
+    /// Unsuccessful status codes are not errors, they are handled by the client. Transports
+    /// other than reqwest report failed requests with `WarframeError::custom_transport`, so
+    /// timeouts and connection errors are retried
+    ///
+    async fn get(&self, url: &Url, headers: HeaderMap) -> Result<TransportResponse>;
+}
+
+///
+/// Default transport, sends requests with a `reqwest::Client`
+///
+#[derive(Debug, Clone, Default)]
+pub struct ReqwestTransport {
+    http: HttpClient,
+}
+
+impl ReqwestTransport {
+    pub fn new(http: HttpClient) -> Self {
+        Self { http }
+    }
+}
+
+#[async_trait]
+impl Transport for ReqwestTransport {
+    async fn get(&self, url: &Url, headers: HeaderMap) -> Result<TransportResponse> {
+        let res = self.http.get(url.clone()).headers(headers).send().await?;
+        Ok(TransportResponse {
+            status: res.status(),
+            headers: res.headers().clone(),
+            body: res.text().await?,
+        })
+    }
+}
+
+///
+/// In-memory transport that answers with fixed responses, for testing code built on top of
+/// `WarframeClient` against specific worldstates
+///
+/// Responses are registered per url path, e.g. `/pc/alerts`, or per path and query, e.g.
+/// `/pc/alerts?language=de`, which takes precedence. If multiple responses are registered for the
+/// same path they are returned in order, the last one repeats. Unknown paths are answered with
+/// `404 Not Found`.
+///
+/// ```
+/// # use warframestat_rs::transport::FixtureTransport;
+/// # use warframestat_rs::WarframeClient;
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let fixtures = FixtureTransport::new().with_json("/pc/alerts", "[]");
+/// let client = WarframeClient::builder()
+///     .build_with_transport(fixtures.clone())
+///     .unwrap();
+/// assert!(client.get_alerts(None, None).await.unwrap().is_empty());
+/// assert_eq!(fixtures.requests().len(), 1);
+/// # });
+/// ```
+///
+#[derive(Debug, Clone, Default)]
+pub struct FixtureTransport {
+    responses: Arc<Mutex<HashMap<String, VecDeque<TransportResponse>>>>,
+    requests: Arc<Mutex<Vec<(Url, HeaderMap)>>>,
+}
+
+impl FixtureTransport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    ///
+    /// Answer requests to `path` with `200 OK` and `json`
+    ///
+    pub fn with_json(self, path: &str, json: impl Into<String>) -> Self {
+        self.with_response(path, TransportResponse::ok(json))
+    }
+
+    ///
+    /// Answer the next request to `path` with `response`
+    ///
+    pub fn with_response(self, path: &str, response: TransportResponse) -> Self {
+        self.push_response(path, response);
+        self
+    }
+
+    pub fn push_response(&self, path: &str, response: TransportResponse) {
+        let mut responses = self.responses.lock().unwrap();
+        responses
+            .entry(path.to_string())
+            .or_default()
+            .push_back(response);
+    }
+
+    ///
+    /// Every request made so far, with the headers the client added
+    ///
+    pub fn requests(&self) -> Vec<(Url, HeaderMap)> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+#[async_trait]
+impl Transport for FixtureTransport {
+    async fn get(&self, url: &Url, headers: HeaderMap) -> Result<TransportResponse> {
+        self.requests.lock().unwrap().push((url.clone(), headers));
+        let mut responses = self.responses.lock().unwrap();
+        let with_query = format!("{}?{}", url.path(), url.query().unwrap_or_default());
+        let key = if responses.contains_key(&with_query) {
+            with_query
+        } else {
+            url.path().to_string()
+        };
+        let response = match responses.get_mut(&key) {
+            Some(queue) if queue.len() > 1 => queue.pop_front(),
+            Some(queue) => queue.front().cloned(),
+            None => None,
+        };
+        Ok(response.unwrap_or_else(|| TransportResponse::new(StatusCode::NOT_FOUND, "")))
+    }
+}
@@ -0,0 +1,241 @@
+//!
+//! Background polling of a platform's worldstate, publishing the changes as `WorldstateEvent`s
+//!
+use crate::diff::{diff, WorldstateEvent};
+use crate::error::WarframeError;
+use crate::model::{EarliestExpiry, Language, Platform, PlatformType};
+use crate::transport::Transport;
+use crate::WarframeClient;
+use chrono::Local;
+use futures::future::{select, Either};
+use futures::Stream;
+use std::time::Duration;
+use tokio::sync::{broadcast, oneshot, watch};
+use tokio::task::JoinHandle;
+
+///
+/// How often a `Watcher` polls and how it backs off after errors
+///
+#[derive(Debug, Clone)]
+pub struct WatchOptions {
+    /// longest time between two polls, polls happen earlier when the cached worldstate expires
+    pub interval: Duration,
+    /// shortest time between two polls
+    pub min_interval: Duration,
+    /// upper bound for the delay after consecutive failed polls
+    pub max_backoff: Duration,
+    /// how many events a slow subscriber may fall behind before it misses some
+    pub capacity: usize,
+}
+
+impl Default for WatchOptions {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(60),
+            min_interval: Duration::from_secs(10),
+            max_backoff: Duration::from_secs(10 * 60),
+            capacity: 256,
+        }
+    }
+}
+
+impl WatchOptions {
+    ///
+    /// Delay before the next poll after `failures` consecutive failed polls
+    ///
+    fn backoff(&self, failures: u32) -> Duration {
+        let factor = 2u32.saturating_pow(failures.saturating_sub(1));
+        self.interval
+            .checked_mul(factor)
+            .unwrap_or(self.max_backoff)
+            .min(self.max_backoff)
+    }
+}
+
+///
+/// Handle to a background task polling a platform, created by `WarframeClient::watch`
+///
+/// Dropping the watcher stops the task, `shutdown` also waits for it to finish
+///
+#[derive(Debug)]
+pub struct Watcher {
+    events: broadcast::Sender<WorldstateEvent>,
+    latest: watch::Receiver<Option<Platform>>,
+    last_error: watch::Receiver<Option<WarframeError>>,
+    shutdown: oneshot::Sender<()>,
+    handle: JoinHandle<()>,
+}
+
+impl Watcher {
+    ///
+    /// Receive every change event from now on
+    ///
+    pub fn subscribe(&self) -> broadcast::Receiver<WorldstateEvent> {
+        self.events.subscribe()
+    }
+
+    ///
+    /// Every change event from now on as a stream, events a slow consumer missed are skipped
+    ///
+    pub fn events(&self) -> impl Stream<Item = WorldstateEvent> {
+        futures::stream::unfold(self.subscribe(), |mut rx| async move {
+            loop {
+                match rx.recv().await {
+                    Ok(event) => return Some((event, rx)),
+                    Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => return None,
+                }
+            }
+        })
+    }
+
+    ///
+    /// The latest polled worldstate, `None` until the first poll succeeded
+    ///
+    pub fn latest(&self) -> watch::Receiver<Option<Platform>> {
+        self.latest.clone()
+    }
+
+    ///
+    /// The error of the last poll, `None` if it succeeded
+    ///
+    pub fn last_error(&self) -> watch::Receiver<Option<WarframeError>> {
+        self.last_error.clone()
+    }
+
+    ///
+    /// Stop polling and wait for the background task to finish
+    ///
+    pub async fn shutdown(self) {
+        let _ = self.shutdown.send(());
+        let _ = self.handle.await;
+    }
+}
+
+impl<T: Transport + Clone + 'static> WarframeClient<T> {
+    ///
+    /// Spawn a task on the current tokio runtime polling the worldstate of `platform`
+    ///
+    /// Polls happen when the cached worldstate expires, but at most `options.interval` apart
+    ///
+    pub fn watch(
+        &self,
+        platform: impl Into<Option<PlatformType>>,
+        lang: impl Into<Option<Language>>,
+        options: WatchOptions,
+    ) -> Watcher {
+        let platform = platform.into();
+        let lang = lang.into();
+        let (events, _) = broadcast::channel(options.capacity.max(1));
+        let (latest_tx, latest) = watch::channel(None);
+        let (error_tx, last_error) = watch::channel(None);
+        let (shutdown, mut shutdown_rx) = oneshot::channel();
+
+        let client = self.clone();
+        let sender = events.clone();
+        let handle = tokio::spawn(async move {
+            let mut failures = 0;
+            loop {
+                let delay = match client.get_platform(platform, lang).await {
+                    Ok(new) => {
+                        failures = 0;
+                        let delay = client.poll_delay(&new, &options);
+                        if let Some(old) = latest_tx.borrow().as_ref() {
+                            for event in diff(old, &new) {
+                                // no subscribers is fine, the event is just dropped
+                                let _ = sender.send(event);
+                            }
+                        }
+                        latest_tx.send_replace(Some(new));
+                        error_tx.send_replace(None);
+                        delay
+                    }
+                    Err(err) => {
+                        failures += 1;
+                        error_tx.send_replace(Some(err));
+                        options.backoff(failures)
+                    }
+                };
+                let sleep = Box::pin(tokio::time::sleep(delay));
+                if let Either::Right(_) = select(sleep, &mut shutdown_rx).await {
+                    break;
+                }
+            }
+        });
+
+        Watcher {
+            events,
+            latest,
+            last_error,
+            shutdown,
+            handle,
+        }
+    }
+
+    ///
+    /// Time until the cache entry of `platform` expires, clamped by the watch options
+    ///
+    fn poll_delay(&self, platform: &Platform, options: &WatchOptions) -> Duration {
+        let expiry = match self.cache_policy.enabled {
+            true => self
+                .cache_policy
+                .expiration_time(platform.earliest_expiry()),
+            false => None,
+        };
+        expiry
+            .and_then(|expiry| (expiry - Local::now()).to_std().ok())
+            .unwrap_or(options.interval)
+            .clamp(
+                options.min_interval,
+                options.interval.max(options.min_interval),
+            )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::WatchOptions;
+    use crate::diff::WorldstateEvent;
+    use crate::model::*;
+    use crate::transport::FixtureTransport;
+    use crate::{CachePolicy, RetryPolicy, WarframeClient};
+    use futures::StreamExt;
+    use std::fs;
+    use std::path::PathBuf;
+    use std::time::Duration;
+
+    #[tokio::test]
+    pub async fn test_watch() -> Result<(), Box<dyn std::error::Error>> {
+        let mut test_json = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_json.push("resources/test/pc_test.json");
+        let json = fs::read_to_string(test_json)?;
+        let mut night: Platform = serde_json::from_str(&json)?;
+        night.cetus_cycle.state = "night".to_string();
+
+        let fixtures = FixtureTransport::new()
+            .with_response("/pc", crate::TransportResponse::new(503.try_into()?, ""))
+            .with_json("/pc", &json)
+            .with_json("/pc", &serde_json::to_string(&night)?);
+        let client = WarframeClient::builder()
+            .cache_policy(CachePolicy::disabled())
+            .retry_policy(RetryPolicy::none())
+            .build_with_transport(fixtures)?;
+        let options = WatchOptions {
+            interval: Duration::from_millis(10),
+            min_interval: Duration::ZERO,
+            max_backoff: Duration::from_millis(10),
+            ..WatchOptions::default()
+        };
+        let watcher = client.watch(None, None, options);
+        let mut events = Box::pin(watcher.events());
+        let event = tokio::time::timeout(Duration::from_secs(5), events.next()).await?;
+        assert!(matches!(event, Some(WorldstateEvent::CetusCycleChanged(_))));
+        let latest = watcher.latest().borrow().clone();
+        assert_eq!(latest.unwrap().cetus_cycle.state, "night");
+        assert!(watcher.last_error().borrow().is_none());
+
+        watcher.shutdown().await;
+        assert_eq!(events.next().await, None);
+        Ok(())
+    }
+}
@@ -0,0 +1,654 @@
+//!
+//! Post worldstate entities to Discord or generic JSON webhooks
+//!
+use crate::diff::WorldstateEvent;
+use crate::error::{self, WarframeError};
+use crate::model::*;
+use crate::retry::{parse_retry_after, RetryEvent, RetryPolicy};
+use chrono::{DateTime, Local};
+use reqwest::header::RETRY_AFTER;
+use reqwest::{Client as HttpClient, StatusCode};
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use url::Url;
+
+/// discord allows at most 25 fields per embed
+const MAX_FIELDS: usize = 25;
+
+const ALERT_COLOR: u32 = 0xE6B422;
+const FISSURE_COLOR: u32 = 0x4C8BF5;
+const INVASION_COLOR: u32 = 0xC0392B;
+const SORTIE_COLOR: u32 = 0x8E44AD;
+const VOID_TRADER_COLOR: u32 = 0x1ABC9C;
+const NEWS_COLOR: u32 = 0x95A5A6;
+
+const DEFAULT_RETENTION: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+///
+/// A discord webhook message
+///
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DiscordMessage {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    pub embeds: Vec<Embed>,
+}
+
+///
+/// A discord embed, see <https://discord.com/developers/docs/resources/channel#embed-object>
+///
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Embed {
+    pub title: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub description: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    pub color: u32,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub fields: Vec<EmbedField>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thumbnail: Option<EmbedImage>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub footer: Option<EmbedFooter>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<DateTime<Local>>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EmbedField {
+    pub name: String,
+    pub value: String,
+    pub inline: bool,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EmbedImage {
+    pub url: String,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EmbedFooter {
+    pub text: String,
+}
+
+impl Embed {
+    fn field(mut self, name: impl Into<String>, value: impl Into<String>, inline: bool) -> Self {
+        let value = value.into();
+        // discord rejects embeds with empty field values
+        if self.fields.len() < MAX_FIELDS && !value.is_empty() {
+            self.fields.push(EmbedField {
+                name: name.into(),
+                value,
+                inline,
+            });
+        }
+        self
+    }
+
+    fn expires(mut self, expiry: DateTime<Local>) -> Self {
+        self.footer = Some(EmbedFooter {
+            text: "Expires".to_string(),
+        });
+        self.timestamp = Some(expiry);
+        self
+    }
+}
+
+///
+/// A worldstate entity that can be posted to a webhook
+///
+/// Dispatched entities are forgotten once their `EarliestExpiry` has passed
+///
+pub trait Notification: Serialize + EarliestExpiry {
+    /// the kind of entity, e.g. "fissure", used in json payloads and for dedupe
+    const KIND: &'static str;
+
+    fn id(&self) -> &str;
+
+    fn embed(&self) -> Embed;
+}
+
+impl Notification for Alert {
+    const KIND: &'static str = "alert";
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn embed(&self) -> Embed {
+        let mission = &self.mission;
+        Embed {
+            title: format!("{} - {}", mission.type_field, mission.node),
+            description: mission.reward.as_string.clone(),
+            color: ALERT_COLOR,
+            thumbnail: thumbnail(&mission.reward.thumbnail),
+            ..Embed::default()
+        }
+        .field("Faction", &mission.faction, true)
+        .field(
+            "Level",
+            format!("{}-{}", mission.min_enemy_level, mission.max_enemy_level),
+            true,
+        )
+        .expires(self.expiry)
+    }
+}
+
+impl Notification for Fissure {
+    const KIND: &'static str = "fissure";
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn embed(&self) -> Embed {
+        let mut title = format!("{} {}", self.tier, self.mission_type);
+        if self.is_hard {
+            title.push_str(" (Steel Path)");
+        }
+        if self.is_storm {
+            title.push_str(" (Void Storm)");
+        }
+        Embed {
+            title,
+            description: self.node.clone(),
+            color: FISSURE_COLOR,
+            ..Embed::default()
+        }
+        .field("Enemy", &self.enemy, true)
+        .expires(self.expiry)
+    }
+}
+
+impl Notification for Invasion {
+    const KIND: &'static str = "invasion";
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn embed(&self) -> Embed {
+        let attacker_reward = self.attacker.reward.as_ref().map(|r| r.as_string.as_str());
+        Embed {
+            title: self.desc.clone(),
+            description: self.node.clone(),
+            color: INVASION_COLOR,
+            ..Embed::default()
+        }
+        .field(
+            format!("{} reward", self.attacking_faction),
+            attacker_reward.unwrap_or_default(),
+            true,
+        )
+        .field(
+            format!("{} reward", self.defender.faction),
+            &self.defender.reward.as_string,
+            true,
+        )
+        .field("Completion", format!("{:.1}%", self.completion), false)
+    }
+}
+
+impl Notification for Sortie {
+    const KIND: &'static str = "sortie";
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn embed(&self) -> Embed {
+        let embed = Embed {
+            title: format!("Sortie: {}", self.boss),
+            description: self.faction.clone(),
+            color: SORTIE_COLOR,
+            ..Embed::default()
+        };
+        self.variants
+            .iter()
+            .fold(embed, |embed, variant| {
+                embed.field(
+                    format!("{} - {}", variant.mission_type, variant.node),
+                    &variant.modifier,
+                    false,
+                )
+            })
+            .expires(self.expiry)
+    }
+}
+
+impl Notification for VoidTrader {
+    const KIND: &'static str = "voidTrader";
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn embed(&self) -> Embed {
+        let embed = Embed {
+            title: self.character.clone(),
+            description: format!("at {}", self.location),
+            color: VOID_TRADER_COLOR,
+            ..Embed::default()
+        };
+        self.inventory
+            .iter()
+            .fold(embed, |embed, item| {
+                embed.field(
+                    &item.item,
+                    format!("{} ducats, {} credits", item.ducats, item.credits),
+                    true,
+                )
+            })
+            .expires(self.expiry)
+    }
+}
+
+impl Notification for News {
+    const KIND: &'static str = "news";
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn embed(&self) -> Embed {
+        Embed {
+            title: self.message.clone(),
+            url: Some(self.link.clone()).filter(|link| !link.is_empty()),
+            color: NEWS_COLOR,
+            thumbnail: thumbnail(&self.image_link),
+            ..Embed::default()
+        }
+    }
+}
+
+fn thumbnail(url: &str) -> Option<EmbedImage> {
+    Some(EmbedImage {
+        url: url.to_string(),
+    })
+    .filter(|image| !image.url.is_empty())
+}
+
+///
+/// Where a `WebhookDispatcher` posts to
+///
+#[derive(Debug, Clone, PartialEq)]
+pub enum WebhookTarget {
+    /// a discord webhook url, receives a `DiscordMessage` with one embed
+    Discord(Url),
+    /// any url, receives `{"kind": ..., "id": ..., "data": <entity>}`
+    Json(Url),
+}
+
+impl WebhookTarget {
+    pub fn url(&self) -> &Url {
+        match self {
+            WebhookTarget::Discord(url) | WebhookTarget::Json(url) => url,
+        }
+    }
+}
+
+///
+/// Posts entities to every target, each entity only once per target
+///
+/// Rate limited posts are retried according to the retry policy, honoring `Retry-After`,
+/// other failed posts aren't retried.
+/// Dispatched entities are remembered until they expire, but at least for the retention
+/// period (7 days by default), so the dispatcher doesn't grow in a long running bot
+///
+#[derive(Debug, Clone)]
+pub struct WebhookDispatcher {
+    http: HttpClient,
+    targets: Vec<WebhookTarget>,
+    retry_policy: RetryPolicy,
+    retention: Duration,
+    /// `target/kind/id` of every delivered entity, with the time to forget it
+    sent: Arc<Mutex<HashMap<String, Option<DateTime<Local>>>>>,
+}
+
+impl WebhookDispatcher {
+    pub fn new(http: HttpClient) -> Self {
+        Self {
+            http,
+            targets: Vec::new(),
+            retry_policy: RetryPolicy::default(),
+            retention: DEFAULT_RETENTION,
+            sent: Arc::default(),
+        }
+    }
+
+    ///
+    /// Add a webhook to post to
+    ///
+    pub fn target(mut self, target: WebhookTarget) -> Self {
+        self.targets.push(target);
+        self
+    }
+
+    ///
+    /// How rate limited posts are retried, other failures aren't retried since the post
+    /// might have arrived anyway
+    ///
+    pub fn retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    ///
+    /// How long a dispatched entity is remembered at least, entities without an expiry
+    /// (invasions, news) are forgotten after that
+    ///
+    pub fn retention(mut self, retention: Duration) -> Self {
+        self.retention = retention;
+        self
+    }
+
+    ///
+    /// Post `entity` to every target it wasn't posted to yet, returns `false` if it was
+    /// already posted to every target
+    ///
+    /// Every target is tried, the first error is returned. Failed targets aren't remembered,
+    /// so dispatching the entity again only posts to them
+    ///
+    pub async fn dispatch<N: Notification>(&self, entity: &N) -> error::Result<bool> {
+        let now = Local::now();
+        // `None` if the retention is too long to represent, the entity is never forgotten then
+        let forget_at = chrono::Duration::from_std(self.retention)
+            .ok()
+            .and_then(|retention| now.checked_add_signed(retention))
+            .map(|retained| {
+                entity
+                    .earliest_expiry()
+                    .map_or(retained, |expiry| expiry.max(retained))
+            });
+        let mut pending = Vec::new();
+        {
+            let mut sent = self.lock();
+            sent.retain(|_, forget_at| forget_at.is_none_or(|forget_at| forget_at > now));
+            for target in &self.targets {
+                if let Entry::Vacant(entry) = sent.entry(sent_key::<N>(target, entity)) {
+                    pending.push((target, entry.key().clone()));
+                    entry.insert(forget_at);
+                }
+            }
+        }
+        let mut result = Ok(!pending.is_empty());
+        for (target, key) in pending {
+            if let Err(err) = self.post_to(target, entity).await {
+                self.lock().remove(&key);
+                if result.is_ok() {
+                    result = Err(err);
+                }
+            }
+        }
+        result
+    }
+
+    ///
+    /// Post the entity that started with `event`, returns `false` for other events and duplicates
+    ///
+    pub async fn dispatch_event(&self, event: &WorldstateEvent) -> error::Result<bool> {
+        match event {
+            WorldstateEvent::AlertStarted(alert) => self.dispatch(alert.as_ref()).await,
+            WorldstateEvent::FissureStarted(fissure) => self.dispatch(fissure.as_ref()).await,
+            WorldstateEvent::InvasionStarted(invasion) => self.dispatch(invasion.as_ref()).await,
+            WorldstateEvent::SortieRotated(sortie) => self.dispatch(sortie.as_ref()).await,
+            WorldstateEvent::VoidTraderArrived(trader) => self.dispatch(trader.as_ref()).await,
+            _ => Ok(false),
+        }
+    }
+
+    ///
+    /// Forget every dispatched entity, so they will be posted again
+    ///
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, Option<DateTime<Local>>>> {
+        self.sent.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    async fn post_to<N: Notification>(
+        &self,
+        target: &WebhookTarget,
+        entity: &N,
+    ) -> error::Result<()> {
+        match target {
+            WebhookTarget::Discord(url) => {
+                let message = DiscordMessage {
+                    embeds: vec![entity.embed()],
+                    ..DiscordMessage::default()
+                };
+                self.post(url, &message).await
+            }
+            WebhookTarget::Json(url) => {
+                let payload = JsonPayload {
+                    kind: N::KIND,
+                    id: entity.id(),
+                    data: entity,
+                };
+                self.post(url, &payload).await
+            }
+        }
+    }
+
+    async fn post(&self, url: &Url, body: &impl Serialize) -> error::Result<()> {
+        let mut attempt = 1;
+        loop {
+            let err = match self.try_post(url, body).await {
+                Ok(()) => return Ok(()),
+                Err(err) => err,
+            };
+            // a post that timed out or failed with a 5xx might have arrived anyway, only a 429
+            // is safe to retry without posting twice
+            let delay = match err {
+                WarframeError::RateLimited { .. } => self.retry_policy.delay_for(attempt, &err),
+                _ => None,
+            };
+            let Some(delay) = delay else {
+                return Err(err);
+            };
+            self.retry_policy.notify(&RetryEvent {
+                attempt,
+                delay,
+                error: &err,
+            });
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+
+    async fn try_post(&self, url: &Url, body: &impl Serialize) -> error::Result<()> {
+        let res = self.http.post(url.clone()).json(body).send().await?;
+        let status = res.status();
+        if status.is_success() {
+            return Ok(());
+        }
+        let header_retry_after = res.headers().get(RETRY_AFTER).and_then(parse_retry_after);
+        let body = res.text().await?;
+        match status {
+            StatusCode::NOT_FOUND => Err(WarframeError::NotFound {
+                url: url.to_string(),
+            }),
+            StatusCode::TOO_MANY_REQUESTS => Err(WarframeError::RateLimited {
+                retry_after: header_retry_after.or_else(|| discord_retry_after(&body)),
+            }),
+            status => Err(WarframeError::Status { status, body }),
+        }
+    }
+}
+
+///
+/// The body posted to `WebhookTarget::Json`
+///
+#[derive(Serialize)]
+struct JsonPayload<'a, N> {
+    kind: &'static str,
+    id: &'a str,
+    data: &'a N,
+}
+
+///
+/// `target/kind/id` dedupe key of `entity`
+///
+fn sent_key<N: Notification>(target: &WebhookTarget, entity: &N) -> String {
+    format!("{}/{}/{}", target.url(), N::KIND, entity.id())
+}
+
+///
+/// Discord also sends the delay in seconds as `retry_after` in the body of a 429
+///
+fn discord_retry_after(body: &str) -> Option<Duration> {
+    let value: serde_json::Value = serde_json::from_str(body).ok()?;
+    Duration::try_from_secs_f64(value.get("retry_after")?.as_f64()?).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Notification, WebhookDispatcher, WebhookTarget};
+    use crate::model::*;
+    use crate::RetryPolicy;
+    use std::fs;
+    use std::path::PathBuf;
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    ///
+    /// Answers every request with the next of `responses`, returns the address and request bodies
+    ///
+    async fn stand_in(
+        responses: Vec<&'static str>,
+    ) -> std::io::Result<(String, Arc<Mutex<Vec<String>>>)> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = format!("http://{}", listener.local_addr()?);
+        let bodies = Arc::new(Mutex::new(Vec::new()));
+        let received = bodies.clone();
+        tokio::spawn(async move {
+            for response in responses {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let mut request = Vec::new();
+                let mut buf = [0; 4096];
+                let body = loop {
+                    let n = stream.read(&mut buf).await.unwrap();
+                    request.extend_from_slice(&buf[..n]);
+                    let text = String::from_utf8_lossy(&request).to_string();
+                    if let Some((head, body)) = text.split_once("\r\n\r\n") {
+                        let len = head
+                            .lines()
+                            .find_map(|l| {
+                                l.to_lowercase()
+                                    .strip_prefix("content-length: ")
+                                    .map(str::to_string)
+                            })
+                            .and_then(|l| l.parse().ok())
+                            .unwrap_or(0);
+                        if body.len() >= len {
+                            break body.to_string();
+                        }
+                    }
+                };
+                received.lock().unwrap().push(body);
+                stream.write_all(response.as_bytes()).await.unwrap();
+            }
+        });
+        Ok((addr, bodies))
+    }
+
+    #[tokio::test]
+    pub async fn test_webhook_dispatch() -> Result<(), Box<dyn std::error::Error>> {
+        let mut test_json = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_json.push("resources/test/pc_test.json");
+        let platform: Platform = serde_json::from_str(&fs::read_to_string(test_json)?)?;
+        let fissure = &platform.fissures[0];
+        assert_eq!(fissure.embed().title, "Neo Defense (Steel Path)");
+
+        let (addr, bodies) = stand_in(vec![
+            "HTTP/1.1 429 Too Many Requests\r\ncontent-length: 19\r\nconnection: close\r\n\r\n{\"retry_after\":0.0}",
+            "HTTP/1.1 204 No Content\r\ncontent-length: 0\r\nconnection: close\r\n\r\n",
+            "HTTP/1.1 200 OK\r\ncontent-length: 0\r\nconnection: close\r\n\r\n",
+        ])
+        .await?;
+        let mut retry_policy = RetryPolicy::default();
+        retry_policy.base_delay = Duration::ZERO;
+        let dispatcher = WebhookDispatcher::new(reqwest::Client::new())
+            .target(WebhookTarget::Discord(format!("{addr}/discord").parse()?))
+            .target(WebhookTarget::Json(format!("{addr}/json").parse()?))
+            .retry_policy(retry_policy);
+
+        assert!(dispatcher.dispatch(fissure).await?);
+        assert!(!dispatcher.dispatch(fissure).await?);
+        let bodies = bodies.lock().unwrap().clone();
+        assert_eq!(bodies.len(), 3);
+        let discord: serde_json::Value = serde_json::from_str(&bodies[1])?;
+        assert_eq!(discord["embeds"][0]["title"], "Neo Defense (Steel Path)");
+        let json: serde_json::Value = serde_json::from_str(&bodies[2])?;
+        assert_eq!(json["kind"], "fissure");
+        assert_eq!(json["data"]["node"], "Proteus (Neptune)");
+        Ok(())
+    }
+
+    #[tokio::test]
+    pub async fn test_webhook_failed_target() -> Result<(), Box<dyn std::error::Error>> {
+        let mut test_json = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_json.push("resources/test/pc_test.json");
+        let platform: Platform = serde_json::from_str(&fs::read_to_string(test_json)?)?;
+        let alert = &platform.alerts[0];
+
+        let (addr, bodies) = stand_in(vec![
+            "HTTP/1.1 204 No Content\r\ncontent-length: 0\r\nconnection: close\r\n\r\n",
+            "HTTP/1.1 503 Service Unavailable\r\ncontent-length: 0\r\nconnection: close\r\n\r\n",
+            "HTTP/1.1 200 OK\r\ncontent-length: 0\r\nconnection: close\r\n\r\n",
+        ])
+        .await?;
+        let mut retry_policy = RetryPolicy::default();
+        retry_policy.base_delay = Duration::ZERO;
+        let dispatcher = WebhookDispatcher::new(reqwest::Client::new())
+            .target(WebhookTarget::Discord(format!("{addr}/discord").parse()?))
+            .target(WebhookTarget::Json(format!("{addr}/json").parse()?))
+            .retry_policy(retry_policy);
+
+        // a 503 isn't retried, the post might have arrived anyway
+        assert!(dispatcher.dispatch(alert).await.is_err());
+        // only the failed json target is posted to again
+        assert!(dispatcher.dispatch(alert).await?);
+        assert!(!dispatcher.dispatch(alert).await?);
+        let bodies = bodies.lock().unwrap().clone();
+        assert_eq!(bodies.len(), 3);
+        let json: serde_json::Value = serde_json::from_str(&bodies[2])?;
+        assert_eq!(json["kind"], "alert");
+        Ok(())
+    }
+
+    #[tokio::test]
+    pub async fn test_webhook_retention() -> Result<(), Box<dyn std::error::Error>> {
+        let mut test_json = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_json.push("resources/test/pc_test.json");
+        let platform: Platform = serde_json::from_str(&fs::read_to_string(test_json)?)?;
+        // the fissures of the test worldstate are long expired
+        let fissure = &platform.fissures[0];
+
+        let (addr, bodies) = stand_in(vec![
+            "HTTP/1.1 200 OK\r\ncontent-length: 0\r\nconnection: close\r\n\r\n",
+            "HTTP/1.1 200 OK\r\ncontent-length: 0\r\nconnection: close\r\n\r\n",
+        ])
+        .await?;
+        let dispatcher = WebhookDispatcher::new(reqwest::Client::new())
+            .target(WebhookTarget::Json(format!("{addr}/json").parse()?))
+            .retention(Duration::ZERO);
+
+        assert!(dispatcher.dispatch(fissure).await?);
+        // forgotten right away, the expired entry is pruned before dispatching again
+        assert!(dispatcher.dispatch(fissure).await?);
+        assert_eq!(dispatcher.lock().len(), 1);
+        assert_eq!(bodies.lock().unwrap().len(), 2);
+        Ok(())
+    }
+}